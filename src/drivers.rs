@@ -0,0 +1,56 @@
+//! Drivers computing the derivatives of user functions, by seeding their inputs and collecting their outputs.
+//!
+//! The functions passed to drivers take slices of duals, and are typically written generically over `Scalar`s.
+//! Drivers return their results in containers implementing `Zeros`, so that these can be `Vec`s or arrays.
+
+use crate::{Dual, RWAble, Scalar, RW};
+
+mod gradient;
+pub use gradient::{gradient, GradientWorkspace};
+mod hessian;
+pub use hessian::{hessian, packed_index, Dual2};
+mod jacobian;
+pub use jacobian::{jacobian, jacobian_chunked};
+mod jvp;
+pub use jvp::{jvp, jvp_seeded};
+mod sparse;
+pub use sparse::{
+    jacobian_pattern, sparse_jacobian, ColumnColoring, SparseJacobian, SparsityPattern,
+};
+mod sparse_hessian;
+pub use sparse_hessian::{hessian_pattern, sparse_hessian, SparseHessian};
+
+// Sets the values of `inputs` to `x`, and their diffs to the columns start..start + ndiffs of the identity.
+fn seed_identity<C, F>(inputs: &mut [Dual<C, RW, F>], x: &[F], start: usize)
+where
+    C: RWAble<F>,
+    F: Scalar,
+{
+    for (i, (input, &xi)) in inputs.iter_mut().zip(x).enumerate() {
+        *input.val_mut() = xi;
+        for (j, d) in input.diffs_mut().iter_mut().enumerate() {
+            *d = if i == start + j { F::one() } else { F::zero() };
+        }
+    }
+}
+
+// Copies the first diffs of `out`, which must have `ndiffs` of them, in `row`.
+// An output without diffs, a constant such as `Dual::constant(c, 0)`, gives a row of zeros.
+fn copy_diffs<C, F>(row: &mut [F], out: &Dual<C, RW, F>, ndiffs: usize)
+where
+    C: RWAble<F>,
+    F: Scalar,
+{
+    if out.ndiffs() == 0 {
+        row.iter_mut().for_each(|d| *d = F::zero());
+        return;
+    }
+    assert_eq!(
+        out.ndiffs(),
+        ndiffs,
+        "An output has {} diffs, while the inputs have {}.",
+        out.ndiffs(),
+        ndiffs
+    );
+    row.copy_from_slice(&out.diffs()[..row.len()]);
+}
@@ -0,0 +1,146 @@
+use super::{copy_diffs, seed_identity};
+use crate::traits::Zeros;
+use crate::{Dual, Scalar, RW};
+
+/// Input duals kept allocated across gradient computations.
+///
+/// Each call reseeds the inputs in place, rewriting their values and their one-hot derivatives,
+/// so that only the computations done by the function itself allocate.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::GradientWorkspace;
+///
+/// let f = |x: &[Dual<Vec<f64>, RW, f64>]| x[0].clone() * x[1].view();
+/// let mut ws = GradientWorkspace::<Vec<f64>, f64>::new(2);
+/// let mut x = [1., 2.];
+/// let mut grad = [0.; 2];
+/// for _ in 0..10 {
+///     let val = ws.gradient_into(f, &x, &mut grad);
+///     assert_eq!(val, x[0] * x[1]);
+///     assert_eq!(grad, [x[1], x[0]]);
+///     x[0] += 1.;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GradientWorkspace<C, F>
+where
+    C: Zeros<F>,
+    F: Scalar,
+{
+    inputs: Vec<Dual<C, RW, F>>,
+}
+
+impl<C, F> GradientWorkspace<C, F>
+where
+    C: Zeros<F>,
+    F: Scalar,
+{
+    /// Allocates the inputs of a function of `n` variables, each carrying `n` derivatives.
+    pub fn new(n: usize) -> Self {
+        GradientWorkspace {
+            inputs: (0..n).map(|_| Dual::from(C::zeros(n + 1))).collect(),
+        }
+    }
+
+    /// Returns the number of variables.
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns true if there is no variable.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Seeds the inputs at `x` and returns them.
+    pub fn seed(&mut self, x: &[F]) -> &[Dual<C, RW, F>] {
+        assert_eq!(
+            x.len(),
+            self.len(),
+            "Wrong number of variables: {} =/= {}.",
+            x.len(),
+            self.len()
+        );
+        seed_identity(&mut self.inputs, x, 0);
+        &self.inputs
+    }
+
+    /// Writes the gradient of `f` at `x` in `grad`, and returns the value of `f`.
+    pub fn gradient_into<Fun>(&mut self, f: Fun, x: &[F], grad: &mut [F]) -> F
+    where
+        Fun: Fn(&[Dual<C, RW, F>]) -> Dual<C, RW, F>,
+    {
+        let res = f(self.seed(x));
+        copy_diffs(grad, &res, self.len());
+        res.val()
+    }
+
+    /// Returns the value and the gradient of `f` at `x`.
+    pub fn gradient<G, Fun>(&mut self, f: Fun, x: &[F]) -> (F, G)
+    where
+        G: Zeros<F>,
+        Fun: Fn(&[Dual<C, RW, F>]) -> Dual<C, RW, F>,
+    {
+        let mut grad = G::zeros(self.len());
+        let val = self.gradient_into(f, x, grad.rw());
+        (val, grad)
+    }
+}
+
+/// Returns the value and the gradient of `f` at `x`.
+///
+/// The inputs passed to `f` carry one derivative per input, seeded with the identity,
+/// in duals backed by the container type `C`, which can be a `Vec` or an array of size n + 1.
+/// Use a `GradientWorkspace` to keep them allocated across calls.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::gradient;
+///
+/// let f = |x: &[Dual<[f64; 3], RW, f64>]| x[0] * x[1].exp();
+/// let (val, grad): (f64, Vec<f64>) = gradient(f, &[3., 0.]);
+/// assert_eq!(val, 3.);
+/// assert_eq!(grad, vec![1., 3.]);
+/// ```
+pub fn gradient<F, C, G, Fun>(f: Fun, x: &[F]) -> (F, G)
+where
+    F: Scalar,
+    C: Zeros<F>,
+    G: Zeros<F>,
+    Fun: Fn(&[Dual<C, RW, F>]) -> Dual<C, RW, F>,
+{
+    GradientWorkspace::<C, F>::new(x.len()).gradient(f, x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rosenbrock<T: Scalar>(x: &[T]) -> T {
+        (T::one() - x[0]).powi(2) + T::from(100.).unwrap() * (x[1] - x[0].powi(2)).powi(2)
+    }
+
+    #[test]
+    fn test_gradient() {
+        let (a, b) = (0.5_f64, 2.);
+        let expected = [-2. * (1. - a) - 400. * a * (b - a * a), 200. * (b - a * a)];
+        let (val, grad): (f64, [f64; 2]) =
+            gradient(|x: &[Dual<[f64; 3], RW, f64>]| rosenbrock(x), &[a, b]);
+        assert_eq!(val, rosenbrock(&[a, b]));
+        assert_eq!(grad, expected);
+
+        let mut ws = GradientWorkspace::<[f64; 3], f64>::new(2);
+        let (_, first): (f64, Vec<f64>) = ws.gradient(rosenbrock, &[1., 1.]);
+        assert_eq!(first, vec![0., 0.]);
+        let (_, second): (f64, Vec<f64>) = ws.gradient(rosenbrock, &[a, b]);
+        assert_eq!(second, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_len() {
+        let mut ws = GradientWorkspace::<Vec<f64>, f64>::new(2);
+        ws.seed(&[1., 2., 3.]);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::traits::Zeros;
+use crate::{Dual, Scalar, RW};
+use num_traits::Float;
+
+/// A dual number nesting another one, used to compute second derivatives.
+///
+/// When the inner derivatives are seeded along some directions and the outer one along another,
+/// the outer derivative of the inner derivatives are the mixed second derivatives along these directions,
+/// as with a `HyperDual`. Unlike the latter, `Dual2` is a `Scalar`, so that it can go through generic code.
+/// The inner duals hold `N - 1` derivatives, one by default.
+pub type Dual2<F, const N: usize = 2> = Dual<[Dual<[F; N], RW, F>; 2], RW, Dual<[F; N], RW, F>>;
+
+// Seeds the inner derivative `inner`, if any, and the outer derivative if `outer` is true.
+fn seed<F: Scalar + Float, const N: usize>(
+    value: F,
+    inner: Option<usize>,
+    outer: bool,
+) -> Dual2<F, N> {
+    let mut val = [F::zero(); N];
+    val[0] = value;
+    if let Some(k) = inner {
+        val[k + 1] = F::one();
+    }
+    let mut diff = [F::zero(); N];
+    if outer {
+        diff[0] = F::one();
+    }
+    Dual::from([Dual::from(val), Dual::from(diff)])
+}
+
+/// Returns the position of the entry (i, j) of a symmetric matrix packed by `hessian`.
+///
+/// The lower triangle is stored row by row: (0, 0), (1, 0), (1, 1), (2, 0), and so on.
+///
+/// ```
+/// # use fwd_ad::drivers::packed_index;
+/// assert_eq!(packed_index(2, 1), 4);
+/// assert_eq!(packed_index(1, 2), 4);
+/// ```
+pub fn packed_index(i: usize, j: usize) -> usize {
+    let (i, j) = if i >= j { (i, j) } else { (j, i) };
+    i * (i + 1) / 2 + j
+}
+
+/// Returns the gradient and the Hessian of `f` at `x`.
+///
+/// The Hessian is symmetric, and packed in n(n+1)/2 entries as described in `packed_index`.
+/// Each row i of its lower triangle is computed by seeding the outer derivatives of the `Dual2`s along the input i,
+/// and their `N - 1` inner derivatives along as many inputs j ≤ i at a time.
+/// `f` is thus evaluated n times when `N` is greater than n, the number of inputs,
+/// and n(n+1)/2 times with the default `N = 2`.
+///
+/// # Panics
+///
+/// Panics if `N` is smaller than 2.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::{hessian, Dual2};
+/// use fwd_ad::traits::Scalar;
+///
+/// fn rosenbrock<T: Scalar>(x: &[T]) -> T {
+///     (T::one() - x[0]).powi(2) + T::from(100.).unwrap() * (x[1] - x[0].powi(2)).powi(2)
+/// }
+///
+/// let (grad, hess): ([f64; 2], Vec<f64>) = hessian(rosenbrock::<Dual2<f64, 3>>, &[1., 1.]);
+/// assert_eq!(grad, [0., 0.]);
+/// assert_eq!(hess, vec![802., -400., 200.]);
+/// ```
+pub fn hessian<F, G, H, Fun, const N: usize>(f: Fun, x: &[F]) -> (G, H)
+where
+    F: Scalar + Float,
+    G: Zeros<F>,
+    H: Zeros<F>,
+    Fun: Fn(&[Dual2<F, N>]) -> Dual2<F, N>,
+{
+    assert!(
+        N >= 2,
+        "hessian: the inner duals must hold at least one derivative"
+    );
+    let width = N - 1;
+    let n = x.len();
+    let mut grad = G::zeros(n);
+    let mut hess = H::zeros(n * (n + 1) / 2);
+    let mut inputs: Vec<Dual2<F, N>> = x.iter().map(|&xk| seed(xk, None, false)).collect();
+    for i in 0..n {
+        for start in (0..=i).step_by(width) {
+            for (k, input) in inputs.iter_mut().enumerate() {
+                let inner = if (start..start + width).contains(&k) {
+                    Some(k - start)
+                } else {
+                    None
+                };
+                *input = seed(x[k], inner, k == i);
+            }
+            // The outer derivative holds the derivative along i and its derivatives along start..start + width
+            let d = f(&inputs).diffs()[0];
+            if start == 0 {
+                grad.rw()[i] = d.val();
+            }
+            for j in start..(start + width).min(i + 1) {
+                hess.rw()[packed_index(i, j)] = d.diffs()[j - start];
+            }
+        }
+    }
+    (grad, hess)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f<T: Scalar>(x: &[T]) -> T {
+        x[0] * x[1].powi(2) * x[2].sin() + (x[0] / x[2]).exp()
+    }
+
+    #[test]
+    fn test_hessian() {
+        let (a, b, c) = (0.3_f64, -1.2_f64, 0.8_f64);
+        let (grad, hess): (Vec<f64>, [f64; 6]) = hessian(f::<Dual2<f64, 4>>, &[a, b, c]);
+        let e = (a / c).exp();
+        let expected_grad = [
+            b * b * c.sin() + e / c,
+            2. * a * b * c.sin(),
+            a * b * b * c.cos() - a * e / (c * c),
+        ];
+        let mut expected_hess = [0.; 6];
+        expected_hess[packed_index(0, 0)] = e / (c * c);
+        expected_hess[packed_index(1, 0)] = 2. * b * c.sin();
+        expected_hess[packed_index(1, 1)] = 2. * a * c.sin();
+        expected_hess[packed_index(2, 0)] = b * b * c.cos() - e / (c * c) - a * e / c.powi(3);
+        expected_hess[packed_index(2, 1)] = 2. * a * b * c.cos();
+        expected_hess[packed_index(2, 2)] =
+            -a * b * b * c.sin() + 2. * a * e / c.powi(3) + a * a * e / c.powi(4);
+        for (g, eg) in grad.iter().zip(expected_grad.iter()) {
+            assert!((g - eg).abs() < 1e-14);
+        }
+        for (h, eh) in hess.iter().zip(expected_hess.iter()) {
+            assert!((h - eh).abs() < 1e-13);
+        }
+        // Two and one inputs per evaluation
+        let res: (Vec<f64>, [f64; 6]) = hessian(f::<Dual2<f64, 3>>, &[a, b, c]);
+        assert_eq!(res, (grad.clone(), hess));
+        let res: (Vec<f64>, [f64; 6]) = hessian(f::<Dual2<f64>>, &[a, b, c]);
+        assert_eq!(res, (grad, hess));
+    }
+
+    #[test]
+    fn test_evaluations() {
+        use std::cell::Cell;
+        let count = Cell::new(0);
+        let g = |x: &[Dual2<f64, 6>]| {
+            count.set(count.get() + 1);
+            f(x)
+        };
+        let _: (Vec<f64>, Vec<f64>) = hessian(g, &[1., 2., 3.]);
+        assert_eq!(count.get(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_array_size() {
+        let _: ([f64; 3], [f64; 3]) = hessian(f::<Dual2<f64>>, &[1., 2., 3.]);
+    }
+}
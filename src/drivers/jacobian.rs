@@ -0,0 +1,217 @@
+use super::{copy_diffs, seed_identity};
+use crate::traits::Zeros;
+use crate::{Dual, Scalar, RW};
+
+/// Returns the value and the Jacobian of `f` at `x`.
+///
+/// The inputs passed to `f` carry one derivative per input, seeded with the identity,
+/// in duals backed by the container type `C`, which can be a `Vec` or an array of size n + 1.
+/// The Jacobian is stored row-major: its entry (i, j), the derivative of the output i with respect to the input j,
+/// is at the position i * n + j.
+/// Outputs without derivatives, such as `Dual::constant(c, 0)`, give rows of zeros.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::jacobian;
+///
+/// let f = |x: &[Dual<[f64; 3], RW, f64>]| vec![x[0] * x[1].view(), x[1] + 2.];
+/// let (val, jac): (Vec<f64>, [f64; 4]) = jacobian(f, &[3., 4.]);
+/// assert_eq!(val, vec![12., 6.]);
+/// assert_eq!(jac, [4., 3., 0., 1.]);
+/// ```
+pub fn jacobian<F, C, V, J, Fun>(f: Fun, x: &[F]) -> (V, J)
+where
+    F: Scalar,
+    C: Zeros<F>,
+    V: Zeros<F>,
+    J: Zeros<F>,
+    Fun: Fn(&[Dual<C, RW, F>]) -> Vec<Dual<C, RW, F>>,
+{
+    let n = x.len();
+    let mut inputs: Vec<Dual<C, RW, F>> = x.iter().map(|_| Dual::from(C::zeros(n + 1))).collect();
+    seed_identity(&mut inputs, x, 0);
+    let outputs = f(&inputs);
+    let m = outputs.len();
+    let mut val = V::zeros(m);
+    let mut jac = J::zeros(m * n);
+    for (i, out) in outputs.iter().enumerate() {
+        val.rw()[i] = out.val();
+        copy_diffs(&mut jac.rw()[i * n..(i + 1) * n], out, n);
+    }
+    (val, jac)
+}
+
+/// Returns the value and the Jacobian of `f` at `x`, computed `chunk` columns at a time.
+///
+/// Unlike [`jacobian`], the inputs passed to `f` only carry `chunk` derivatives,
+/// and `f` is evaluated once per group of `chunk` inputs, the last group being possibly smaller.
+/// This keeps the duals small when there are many inputs:
+/// with arrays of size `chunk + 1` as containers `C`, they can live on the stack.
+/// The Jacobian is stored row-major, as for [`jacobian`].
+///
+/// # Panics
+///
+/// Panics if `chunk` is zero, if `C` cannot hold `chunk` derivatives,
+/// if `f` does not return the same number of outputs on every evaluation,
+/// or if an output has neither `chunk` derivatives nor none.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::jacobian_chunked;
+///
+/// // Three inputs, two at a time
+/// let f = |x: &[Dual<[f64; 3], RW, f64>]| vec![x[0] * x[1].view(), x[1] * x[2].view()];
+/// let (val, jac): (Vec<f64>, Vec<f64>) = jacobian_chunked(f, &[2., 3., 4.], 2);
+/// assert_eq!(val, vec![6., 12.]);
+/// assert_eq!(jac, vec![3., 2., 0., 0., 4., 3.]);
+/// ```
+pub fn jacobian_chunked<F, C, V, J, Fun>(f: Fun, x: &[F], chunk: usize) -> (V, J)
+where
+    F: Scalar,
+    C: Zeros<F>,
+    V: Zeros<F>,
+    J: Zeros<F>,
+    Fun: Fn(&[Dual<C, RW, F>]) -> Vec<Dual<C, RW, F>>,
+{
+    assert!(
+        chunk > 0,
+        "jacobian_chunked: the chunk size must be positive"
+    );
+    let n = x.len();
+    let mut inputs: Vec<Dual<C, RW, F>> =
+        x.iter().map(|_| Dual::from(C::zeros(chunk + 1))).collect();
+    let mut evaluate = |start: usize| {
+        seed_identity(&mut inputs, x, start);
+        f(&inputs)
+    };
+    let outputs = evaluate(0);
+    let m = outputs.len();
+    let mut val = V::zeros(m);
+    let mut jac = J::zeros(m * n);
+    for (i, out) in outputs.iter().enumerate() {
+        val.rw()[i] = out.val();
+    }
+    let mut outputs = Some(outputs);
+    for start in (0..n).step_by(chunk) {
+        let outputs = outputs.take().unwrap_or_else(|| evaluate(start));
+        assert_eq!(
+            outputs.len(),
+            m,
+            "jacobian_chunked: f returned a varying number of outputs"
+        );
+        let width = chunk.min(n - start);
+        for (i, out) in outputs.iter().enumerate() {
+            copy_diffs(
+                &mut jac.rw()[i * n + start..i * n + start + width],
+                out,
+                chunk,
+            );
+        }
+    }
+    (val, jac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f<T: Scalar>(x: &[T]) -> Vec<T> {
+        vec![
+            x[0] * x[1],
+            x[0].sin() + x[1].powi(2),
+            (x[0] * x[1]).exp(),
+            x[1],
+        ]
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let (a, b) = (0.5_f64, -1.5_f64);
+        let e = (a * b).exp();
+        let expected_val = [a * b, a.sin() + b * b, e, b];
+        let expected_jac = [b, a, a.cos(), 2. * b, b * e, a * e, 0., 1.];
+        // Vec backed duals are not `Scalar`s, hence cannot go through f.
+        let fvec = |x: &[Dual<Vec<f64>, RW, f64>]| {
+            let xy = x[0].clone() * x[1].view();
+            vec![
+                xy.clone(),
+                x[0].clone().sin() + x[1].clone().powi(2),
+                xy.exp(),
+                x[1].clone(),
+            ]
+        };
+        let (val, jac): (Vec<f64>, Vec<f64>) = jacobian(fvec, &[a, b]);
+        assert_eq!(val, expected_val);
+        assert_eq!(jac, expected_jac);
+        let (val, jac): ([f64; 4], [f64; 8]) =
+            jacobian(|x: &[Dual<[f64; 3], RW, f64>]| f(x), &[a, b]);
+        assert_eq!(val, expected_val);
+        assert_eq!(jac, expected_jac);
+    }
+
+    #[test]
+    fn test_jacobian_chunked() {
+        let g = |x: &[Dual<[f64; 3], RW, f64>]| {
+            vec![
+                x[0] * x[4].view(),
+                x[1].sin() + x[2].view(),
+                x.iter()
+                    .fold(Dual::from([1., 0., 0.]), |acc, xi| acc * xi.view()),
+            ]
+        };
+        let x = [0.5, -1.5, 2., 0.25, 3.];
+        let (expected_val, expected_jac): (Vec<f64>, Vec<f64>) = jacobian(
+            |x: &[Dual<Vec<f64>, RW, f64>]| {
+                vec![
+                    x[0].clone() * x[4].view(),
+                    x[1].clone().sin() + x[2].view(),
+                    x.iter()
+                        .skip(1)
+                        .fold(x[0].clone(), |acc, xi| acc * xi.view()),
+                ]
+            },
+            &x,
+        );
+        // Chunks of 2, the last one being partial
+        let (val, jac): (Vec<f64>, Vec<f64>) = jacobian_chunked(g, &x, 2);
+        assert_eq!(val, expected_val);
+        assert_eq!(jac, expected_jac);
+        // Chunks of 1, then larger than the number of inputs
+        let (eval, ejac): (Vec<f64>, Vec<f64>) =
+            jacobian(|x: &[Dual<[f64; 3], RW, f64>]| f(x), &[0.5, -1.5]);
+        let (val, jac): (Vec<f64>, [f64; 8]) =
+            jacobian_chunked(|x: &[Dual<[f64; 2], RW, f64>]| f(x), &[0.5, -1.5], 1);
+        assert_eq!(val, eval);
+        assert_eq!(jac.to_vec(), ejac);
+        let (val, jac): (Vec<f64>, Vec<f64>) =
+            jacobian_chunked(|x: &[Dual<[f64; 4], RW, f64>]| f(x), &[0.5, -1.5], 3);
+        assert_eq!(val, eval);
+        assert_eq!(jac, ejac);
+    }
+
+    #[test]
+    fn test_constant_outputs() {
+        let f = |x: &[Dual<Vec<f64>, RW, f64>]| {
+            vec![
+                x[0].clone() * x[1].view(),
+                Dual::<Vec<f64>, RW, f64>::constant(2., 0),
+            ]
+        };
+        let (val, jac): (Vec<f64>, Vec<f64>) = jacobian(f, &[3., 4.]);
+        assert_eq!(val, vec![12., 2.]);
+        assert_eq!(jac, vec![4., 3., 0., 0.]);
+        let (val, jac): (Vec<f64>, Vec<f64>) = jacobian_chunked(f, &[3., 4.], 1);
+        assert_eq!(val, vec![12., 2.]);
+        assert_eq!(jac, vec![4., 3., 0., 0.]);
+        let g = |x: &[Dual<[f64; 3], RW, f64>]| vec![x[0] + x[1], num_traits::One::one()];
+        let (_, jac): (Vec<f64>, Vec<f64>) = jacobian(g, &[3., 4.]);
+        assert_eq!(jac, vec![1., 1., 0., 0.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "An output has 1 diffs, while the inputs have 2.")]
+    fn test_wrong_output_ndiffs() {
+        let f = |_: &[Dual<Vec<f64>, RW, f64>]| vec![Dual::<Vec<f64>, RW, f64>::constant(2., 1)];
+        let _: (Vec<f64>, Vec<f64>) = jacobian(f, &[3., 4.]);
+    }
+}
@@ -0,0 +1,183 @@
+use super::copy_diffs;
+use crate::traits::Zeros;
+use crate::{Dual, Scalar, RW};
+
+/// Returns the value of `f` at `x`, and its directional derivative along `v`, that is the product J·v.
+///
+/// The inputs passed to `f` carry a single derivative, seeded with the components of `v`,
+/// so `C` can be a `Vec` or an array of size 2, and `f` is evaluated once whatever the number of inputs.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::jvp;
+///
+/// let f = |x: &[Dual<[f64; 2], RW, f64>]| vec![x[0] * x[1].view(), x[1] + 2.];
+/// let (val, jv): (Vec<f64>, Vec<f64>) = jvp(f, &[3., 4.], &[1., -1.]);
+/// assert_eq!(val, vec![12., 6.]);
+/// assert_eq!(jv, vec![4. - 3., -1.]);
+/// ```
+pub fn jvp<F, C, V, Fun>(f: Fun, x: &[F], v: &[F]) -> (V, V)
+where
+    F: Scalar,
+    C: Zeros<F>,
+    V: Zeros<F>,
+    Fun: Fn(&[Dual<C, RW, F>]) -> Vec<Dual<C, RW, F>>,
+{
+    jvp_seeded(f, x, v, 1)
+}
+
+/// Returns the value of `f` at `x`, and the product J·S of its Jacobian with the seed matrix S.
+///
+/// S has one row per input and `p` columns, the tangents, and is stored row-major:
+/// its entry (i, k) is at the position i * p + k.
+/// The inputs passed to `f` carry `p` derivatives, the row i of S for the input i,
+/// so `C` can be a `Vec` or an array of size p + 1.
+/// The product is returned row-major too, its entry (i, k) being the derivative of the output i along the tangent k,
+/// at the position i * p + k.
+/// Outputs without derivatives, such as `Dual::constant(c, 0)`, give rows of zeros.
+///
+/// # Panics
+///
+/// Panics if the length of `s` is not the number of inputs times `p`.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::jvp_seeded;
+///
+/// let f = |x: &[Dual<[f64; 3], RW, f64>]| vec![x[0] * x[1].view() * x[2].view()];
+/// // Derivatives along x0 + x1 and along x2
+/// let s = [1., 0.,
+///          1., 0.,
+///          0., 1.];
+/// let (val, js): ([f64; 1], [f64; 2]) = jvp_seeded(f, &[2., 3., 4.], &s, 2);
+/// assert_eq!(val, [24.]);
+/// assert_eq!(js, [12. + 8., 6.]);
+/// ```
+pub fn jvp_seeded<F, C, V, J, Fun>(f: Fun, x: &[F], s: &[F], p: usize) -> (V, J)
+where
+    F: Scalar,
+    C: Zeros<F>,
+    V: Zeros<F>,
+    J: Zeros<F>,
+    Fun: Fn(&[Dual<C, RW, F>]) -> Vec<Dual<C, RW, F>>,
+{
+    assert_eq!(
+        s.len(),
+        x.len() * p,
+        "jvp_seeded: the seed matrix must have one row of {} tangents per input",
+        p
+    );
+    let inputs: Vec<Dual<C, RW, F>> = x
+        .iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let mut input = Dual::from(C::zeros(p + 1));
+            *input.val_mut() = xi;
+            input.diffs_mut().copy_from_slice(&s[i * p..(i + 1) * p]);
+            input
+        })
+        .collect();
+    let outputs = f(&inputs);
+    let m = outputs.len();
+    let mut val = V::zeros(m);
+    let mut js = J::zeros(m * p);
+    for (i, out) in outputs.iter().enumerate() {
+        val.rw()[i] = out.val();
+        copy_diffs(&mut js.rw()[i * p..(i + 1) * p], out, p);
+    }
+    (val, js)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::jacobian;
+
+    fn f<T: Scalar>(x: &[T]) -> Vec<T> {
+        vec![
+            x[0] * x[1] * x[2],
+            x[0].sin() + x[2].powi(2),
+            (x[1] / x[0]).exp(),
+        ]
+    }
+
+    #[test]
+    fn test_jvp() {
+        let x = [0.5, -1.5, 2.];
+        let (val, jac): (Vec<f64>, Vec<f64>) = jacobian(|x: &[Dual<[f64; 4], RW, f64>]| f(x), &x);
+        let matvec = |v: &[f64]| -> Vec<f64> {
+            jac.chunks(3)
+                .map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum())
+                .collect()
+        };
+
+        let v = [1., -2., 0.5];
+        let (jval, jv): (Vec<f64>, Vec<f64>) = jvp(|x: &[Dual<[f64; 2], RW, f64>]| f(x), &x, &v);
+        assert_eq!(jval, val);
+        for (a, b) in jv.iter().zip(matvec(&v)) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // The identity seed gives the Jacobian back.
+        let s = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+        let (_, js): ([f64; 3], Vec<f64>) = jvp_seeded(
+            |x: &[Dual<Vec<f64>, RW, f64>]| {
+                let xy = x[0].clone() * x[1].view();
+                vec![
+                    xy * x[2].view(),
+                    x[0].clone().sin() + x[2].clone().powi(2),
+                    (x[1].clone() / x[0].view()).exp(),
+                ]
+            },
+            &x,
+            &s,
+            3,
+        );
+        for (a, b) in js.iter().zip(&jac) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // Two tangents at once
+        let w = [0., 3., -1.];
+        let s: Vec<f64> = v.iter().zip(&w).flat_map(|(&a, &b)| vec![a, b]).collect();
+        let (_, js): (Vec<f64>, Vec<f64>) =
+            jvp_seeded(|x: &[Dual<[f64; 3], RW, f64>]| f(x), &x, &s, 2);
+        for (i, (a, b)) in matvec(&v).into_iter().zip(matvec(&w)).enumerate() {
+            assert!((js[2 * i] - a).abs() < 1e-12);
+            assert!((js[2 * i + 1] - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_no_tangent() {
+        let x = [0.5, -1.5, 2.];
+        let (val, js): (Vec<f64>, Vec<f64>) =
+            jvp_seeded(|x: &[Dual<[f64; 1], RW, f64>]| f(x), &x, &[], 0);
+        assert_eq!(val, f(&x));
+        assert!(js.is_empty());
+    }
+
+    #[test]
+    fn test_constant_outputs() {
+        let f = |x: &[Dual<Vec<f64>, RW, f64>]| {
+            vec![
+                x[0].clone().exp(),
+                Dual::<Vec<f64>, RW, f64>::constant(2., 0),
+            ]
+        };
+        let (val, js): (Vec<f64>, Vec<f64>) = jvp(f, &[0.], &[3.]);
+        assert_eq!(val, vec![1., 2.]);
+        assert_eq!(js, vec![3., 0.]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_seed_len() {
+        let _: (Vec<f64>, Vec<f64>) = jvp_seeded(
+            |x: &[Dual<[f64; 3], RW, f64>]| f(x),
+            &[1., 2., 3.],
+            &[1., 0.],
+            2,
+        );
+    }
+}
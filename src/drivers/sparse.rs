@@ -0,0 +1,603 @@
+use super::seed_identity;
+use crate::traits::Zeros;
+use crate::{Dual, Pattern, Scalar, RW};
+
+/// The positions of the possibly nonzero entries of a Jacobian.
+///
+/// The pattern is stored row by row: for each output, the sorted indices of the inputs it depends on.
+///
+/// ```
+/// use fwd_ad::drivers::SparsityPattern;
+///
+/// // y0 = f(x0, x1), y1 = g(x1, x2)
+/// let pattern = SparsityPattern::new(3, vec![vec![0, 1], vec![1, 2]]);
+/// assert_eq!(pattern.nrows(), 2);
+/// assert_eq!(pattern.nnz(), 4);
+/// assert!(pattern.contains(1, 2));
+/// assert!(!pattern.contains(0, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SparsityPattern {
+    ncols: usize,
+    rows: Vec<Vec<usize>>,
+}
+
+impl SparsityPattern {
+    /// Builds a pattern with `ncols` columns from the column indices of each row.
+    ///
+    /// The indices of each row are sorted and deduplicated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is not smaller than `ncols`.
+    pub fn new(ncols: usize, mut rows: Vec<Vec<usize>>) -> Self {
+        for row in rows.iter_mut() {
+            row.sort_unstable();
+            row.dedup();
+            if let Some(&j) = row.last() {
+                assert!(
+                    j < ncols,
+                    "SparsityPattern: column index {} out of bounds ({} columns)",
+                    j,
+                    ncols
+                );
+            }
+        }
+        SparsityPattern { ncols, rows }
+    }
+
+    /// Builds a pattern with `nrows` rows and `ncols` columns from the (row, column) positions of its entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a position is out of bounds.
+    pub fn from_entries(nrows: usize, ncols: usize, entries: &[(usize, usize)]) -> Self {
+        let mut rows = vec![Vec::new(); nrows];
+        for &(i, j) in entries {
+            rows[i].push(j);
+        }
+        Self::new(ncols, rows)
+    }
+
+    /// Returns the number of rows, that is of outputs.
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the number of columns, that is of inputs.
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Returns the number of entries.
+    pub fn nnz(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    /// Returns the sorted column indices of the entries of the row `i`.
+    pub fn row(&self, i: usize) -> &[usize] {
+        &self.rows[i]
+    }
+
+    /// Returns true if the pattern has an entry at (i, j).
+    pub fn contains(&self, i: usize, j: usize) -> bool {
+        self.rows[i].binary_search(&j).is_ok()
+    }
+
+    /// Computes a coloring of the columns such that no two columns of the same color share a row.
+    ///
+    /// The columns are colored greedily, in order, each one with the smallest color not used by its neighbours.
+    ///
+    /// ```
+    /// use fwd_ad::drivers::SparsityPattern;
+    ///
+    /// // A tridiagonal pattern only needs three colors, whatever its size.
+    /// let n = 100;
+    /// let rows = (0..n)
+    ///     .map(|i: usize| (i.saturating_sub(1)..(i + 2).min(n)).collect())
+    ///     .collect();
+    /// let coloring = SparsityPattern::new(n, rows).column_coloring();
+    /// assert_eq!(coloring.ncolors(), 3);
+    /// ```
+    pub fn column_coloring(&self) -> ColumnColoring {
+        let mut cols = vec![Vec::new(); self.ncols];
+        for (i, row) in self.rows.iter().enumerate() {
+            for &j in row {
+                cols[j].push(i);
+            }
+        }
+        let mut colors = vec![usize::MAX; self.ncols];
+        // forbidden[c] == j when the color c is used by a neighbour of the column j
+        let mut forbidden = vec![usize::MAX; self.ncols];
+        let mut ncolors = 0;
+        for j in 0..self.ncols {
+            for &i in &cols[j] {
+                for &k in &self.rows[i] {
+                    if colors[k] != usize::MAX {
+                        forbidden[colors[k]] = j;
+                    }
+                }
+            }
+            let color = (0..).find(|&c| forbidden[c] != j).unwrap();
+            colors[j] = color;
+            ncolors = ncolors.max(color + 1);
+        }
+        ColumnColoring { colors, ncolors }
+    }
+
+    /// Computes a star coloring of the columns of a symmetric pattern, such as the one of a Hessian.
+    ///
+    /// Seen as a graph, where i and j are adjacent when the pattern has an entry at (i, j),
+    /// adjacent columns get distinct colors, and every path on four columns uses at least three colors.
+    /// This allows to read every entry of a symmetric matrix directly from its products with the color seeds,
+    /// with fewer colors than a column coloring.
+    /// The columns are colored greedily, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is not square. The pattern is assumed to be symmetric.
+    ///
+    /// ```
+    /// use fwd_ad::drivers::SparsityPattern;
+    ///
+    /// // An arrowhead pattern, where the column 0 is adjacent to all the others
+    /// let n = 100;
+    /// let rows = (0..n)
+    ///     .map(|i| if i == 0 { (0..n).collect() } else { vec![0, i] })
+    ///     .collect();
+    /// let pattern = SparsityPattern::new(n, rows);
+    /// assert_eq!(pattern.column_coloring().ncolors(), n);
+    /// assert_eq!(pattern.star_coloring().ncolors(), 2);
+    /// ```
+    pub fn star_coloring(&self) -> ColumnColoring {
+        assert_eq!(
+            self.nrows(),
+            self.ncols,
+            "SparsityPattern: star colorings are for square patterns"
+        );
+        let n = self.ncols;
+        let neighbours = |v: usize| self.rows[v].iter().copied().filter(move |&w| w != v);
+        let mut colors = vec![usize::MAX; n];
+        // forbidden[c] == v when the color c cannot be used for the column v
+        let mut forbidden = vec![usize::MAX; n];
+        // count[c] is the number of neighbours of v of color c
+        let mut count = vec![0; n];
+        let mut ncolors = 0;
+        for v in 0..n {
+            for w in neighbours(v).filter(|&w| colors[w] != usize::MAX) {
+                forbidden[colors[w]] = v;
+                count[colors[w]] += 1;
+            }
+            // Taking the color of x would make a path on four columns v, w, x and another one bicolored,
+            // if v or x has another neighbour of the color of w.
+            for w in neighbours(v).filter(|&w| colors[w] != usize::MAX) {
+                let cw = colors[w];
+                for x in neighbours(w) {
+                    let cx = colors[x];
+                    if x == v || cx == usize::MAX || forbidden[cx] == v {
+                        continue;
+                    }
+                    if count[cw] > 1 || neighbours(x).any(|y| y != w && colors[y] == cw) {
+                        forbidden[cx] = v;
+                    }
+                }
+            }
+            for w in neighbours(v).filter(|&w| colors[w] != usize::MAX) {
+                count[colors[w]] = 0;
+            }
+            let color = (0..).find(|&c| forbidden[c] != v).unwrap();
+            colors[v] = color;
+            ncolors = ncolors.max(color + 1);
+        }
+        ColumnColoring { colors, ncolors }
+    }
+}
+
+/// An assignment of colors to the columns of a Jacobian, such that columns of the same color have no common row.
+///
+/// Columns of the same color can share a derivative slot,
+/// so the Jacobian can be computed with as many derivatives as colors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ColumnColoring {
+    colors: Vec<usize>,
+    ncolors: usize,
+}
+
+impl ColumnColoring {
+    /// Returns the number of colors.
+    pub fn ncolors(&self) -> usize {
+        self.ncolors
+    }
+
+    /// Returns the color of each column.
+    pub fn colors(&self) -> &[usize] {
+        &self.colors
+    }
+}
+
+/// A Jacobian stored in the compressed sparse row format.
+///
+/// The column indices and the values of the entries of the row i
+/// are `indices()[indptr()[i]..indptr()[i + 1]]` and `values()[indptr()[i]..indptr()[i + 1]]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseJacobian<F> {
+    ncols: usize,
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<F>,
+}
+
+impl<F: Scalar> SparseJacobian<F> {
+    /// Returns the number of rows, that is of outputs.
+    pub fn nrows(&self) -> usize {
+        self.indptr.len() - 1
+    }
+
+    /// Returns the number of columns, that is of inputs.
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Returns the offsets of the rows in `indices` and `values`.
+    pub fn indptr(&self) -> &[usize] {
+        &self.indptr
+    }
+
+    /// Returns the column indices of the entries.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns the values of the entries.
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    /// Returns the entry (i, j), which is zero outside of the pattern.
+    pub fn get(&self, i: usize, j: usize) -> F {
+        let range = self.indptr[i]..self.indptr[i + 1];
+        match self.indices[range.clone()].binary_search(&j) {
+            Ok(k) => self.values[range.start + k],
+            Err(_) => F::zero(),
+        }
+    }
+
+    /// Iterates over the entries, as (row, column, value), row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, F)> + '_ {
+        self.indptr
+            .windows(2)
+            .enumerate()
+            .flat_map(move |(i, w)| (w[0]..w[1]).map(move |k| (i, self.indices[k], self.values[k])))
+    }
+
+    /// Returns the Jacobian as a dense row-major matrix.
+    pub fn to_dense<J: Zeros<F>>(&self) -> J {
+        let mut dense = J::zeros(self.nrows() * self.ncols);
+        for (i, j, v) in self.iter() {
+            dense.rw()[i * self.ncols + j] = v;
+        }
+        dense
+    }
+}
+
+/// Returns the value and the sparse Jacobian of `f` at `x`, given its sparsity pattern and a coloring of its columns.
+///
+/// The inputs passed to `f` carry one derivative per color,
+/// each input being seeded with a one in the slot of its color,
+/// so `C` can be a `Vec` or an array of size `coloring.ncolors() + 1`.
+/// The Jacobian is then recovered from the derivatives of the outputs:
+/// the entry (i, j) is the derivative of the output i in the slot of the color of j.
+/// Entries outside of the pattern are assumed to be zero, and are not checked.
+///
+/// # Panics
+///
+/// Panics if the sizes of `x`, `pattern`, `coloring` and of the outputs of `f` do not match.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::{sparse_jacobian, SparsityPattern};
+///
+/// // y_i = x_i * x_{i+1}
+/// let f = |x: &[Dual<[f64; 3], RW, f64>]| {
+///     x.windows(2).map(|w| w[0] * w[1].view()).collect::<Vec<_>>()
+/// };
+/// let pattern = SparsityPattern::new(4, vec![vec![0, 1], vec![1, 2], vec![2, 3]]);
+/// let coloring = pattern.column_coloring();
+/// assert_eq!(coloring.ncolors(), 2);
+/// let (val, jac): (Vec<f64>, _) = sparse_jacobian(f, &[1., 2., 3., 4.], &pattern, &coloring);
+/// assert_eq!(val, vec![2., 6., 12.]);
+/// assert_eq!(jac.values(), &[2., 1., 3., 2., 4., 3.]);
+/// assert_eq!(jac.get(2, 3), 3.);
+/// assert_eq!(jac.get(2, 0), 0.);
+/// ```
+pub fn sparse_jacobian<F, C, V, Fun>(
+    f: Fun,
+    x: &[F],
+    pattern: &SparsityPattern,
+    coloring: &ColumnColoring,
+) -> (V, SparseJacobian<F>)
+where
+    F: Scalar,
+    C: Zeros<F>,
+    V: Zeros<F>,
+    Fun: Fn(&[Dual<C, RW, F>]) -> Vec<Dual<C, RW, F>>,
+{
+    let n = x.len();
+    assert_eq!(
+        pattern.ncols(),
+        n,
+        "sparse_jacobian: the pattern must have one column per input"
+    );
+    assert_eq!(
+        coloring.colors().len(),
+        n,
+        "sparse_jacobian: the coloring must have one color per input"
+    );
+    let inputs: Vec<Dual<C, RW, F>> = x
+        .iter()
+        .zip(coloring.colors())
+        .map(|(&xi, &color)| {
+            let mut input = Dual::from(C::zeros(coloring.ncolors() + 1));
+            *input.val_mut() = xi;
+            input.diffs_mut()[color] = F::one();
+            input
+        })
+        .collect();
+    let outputs = f(&inputs);
+    assert_eq!(
+        outputs.len(),
+        pattern.nrows(),
+        "sparse_jacobian: the pattern must have one row per output"
+    );
+    let mut val = V::zeros(outputs.len());
+    let mut indptr = Vec::with_capacity(outputs.len() + 1);
+    let mut indices = Vec::with_capacity(pattern.nnz());
+    let mut values = Vec::with_capacity(pattern.nnz());
+    indptr.push(0);
+    for (i, out) in outputs.iter().enumerate() {
+        val.rw()[i] = out.val();
+        for &j in pattern.row(i) {
+            indices.push(j);
+            // An output without diffs is a constant
+            values.push(if out.ndiffs() == 0 {
+                F::zero()
+            } else {
+                out.diffs()[coloring.colors()[j]]
+            });
+        }
+        indptr.push(indices.len());
+    }
+    let jac = SparseJacobian {
+        ncols: n,
+        indptr,
+        indices,
+        values,
+    };
+    (val, jac)
+}
+
+/// Returns the sparsity pattern of the Jacobian of `f`, a function of `n` inputs.
+///
+/// `f` is evaluated once, on duals of [`Pattern`](../struct.Pattern.html)s seeded with the identity,
+/// so `C` can be a `Vec` or an array of size n + 1.
+/// The pattern is valid for all inputs, as long as `f` does not branch on their values.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::{jacobian_pattern, sparse_jacobian};
+/// use fwd_ad::traits::Scalar;
+///
+/// fn f<T: Scalar>(x: &[T]) -> Vec<T> {
+///     x.windows(2).map(|w| w[0] * w[1]).collect()
+/// }
+///
+/// let pattern = jacobian_pattern(|x: &[Dual<[Pattern; 5], RW, Pattern>]| f(x), 4);
+/// assert_eq!(pattern.row(1), &[1, 2]);
+/// let coloring = pattern.column_coloring();
+/// let (_, jac): (Vec<f64>, _) =
+///     sparse_jacobian(|x: &[Dual<[f64; 3], RW, f64>]| f(x), &[1., 2., 3., 4.], &pattern, &coloring);
+/// assert_eq!(jac.values(), &[2., 1., 3., 2., 4., 3.]);
+/// ```
+pub fn jacobian_pattern<C, Fun>(f: Fun, n: usize) -> SparsityPattern
+where
+    C: Zeros<Pattern>,
+    Fun: Fn(&[Dual<C, RW, Pattern>]) -> Vec<Dual<C, RW, Pattern>>,
+{
+    let mut inputs: Vec<Dual<C, RW, Pattern>> =
+        (0..n).map(|_| Dual::from(C::zeros(n + 1))).collect();
+    seed_identity(&mut inputs, &vec![Pattern(true); n], 0);
+    let rows = f(&inputs)
+        .iter()
+        .map(|out| {
+            out.diffs()
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.is_nonzero())
+                .map(|(j, _)| j)
+                .collect()
+        })
+        .collect();
+    SparsityPattern::new(n, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::jacobian;
+
+    // A chain of reactions x_i -> x_{i+1}, with a catalyst x_0
+    fn rates<T: Scalar>(x: &[T]) -> Vec<T> {
+        let n = x.len();
+        (0..n)
+            .map(|i| {
+                let mut r = x[0] * x[i].sin();
+                if i > 0 {
+                    r -= x[i - 1] * x[i - 1];
+                }
+                if i + 1 < n {
+                    r += x[i + 1].exp();
+                }
+                r
+            })
+            .collect()
+    }
+
+    fn rates_pattern(n: usize) -> SparsityPattern {
+        let rows = (0..n)
+            .map(|i| {
+                let mut row: Vec<usize> = (i.saturating_sub(1)..(i + 2).min(n)).collect();
+                row.push(0);
+                row
+            })
+            .collect();
+        SparsityPattern::new(n, rows)
+    }
+
+    #[test]
+    fn test_pattern() {
+        let pattern = SparsityPattern::from_entries(2, 3, &[(1, 2), (0, 1), (1, 0), (1, 2)]);
+        assert_eq!(pattern.row(0), &[1]);
+        assert_eq!(pattern.row(1), &[0, 2]);
+        assert_eq!(pattern.nnz(), 3);
+        assert_eq!(pattern, SparsityPattern::new(3, vec![vec![1], vec![2, 0]]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pattern_out_of_bounds() {
+        SparsityPattern::new(2, vec![vec![0, 2]]);
+    }
+
+    #[test]
+    fn test_coloring() {
+        let n = 30;
+        let pattern = rates_pattern(n);
+        let coloring = pattern.column_coloring();
+        // The column 0 shares rows with every other column.
+        assert_eq!(coloring.ncolors(), 4);
+        for i in 0..n {
+            let row = pattern.row(i);
+            for (a, &j) in row.iter().enumerate() {
+                for &k in &row[a + 1..] {
+                    assert_ne!(coloring.colors()[j], coloring.colors()[k]);
+                }
+            }
+        }
+        let empty = SparsityPattern::new(3, vec![vec![], vec![]]);
+        assert_eq!(empty.column_coloring().colors(), &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_jacobian_pattern() {
+        let n = 30;
+        let pattern = jacobian_pattern(
+            |x: &[Dual<Vec<Pattern>, RW, Pattern>]| {
+                (0..n)
+                    .map(|i| {
+                        let mut r = x[0].clone() * x[i].clone().sin().view();
+                        if i > 0 {
+                            r -= x[i - 1].clone() * x[i - 1].view();
+                        }
+                        if i + 1 < n {
+                            r += x[i + 1].clone().exp();
+                        }
+                        r
+                    })
+                    .collect()
+            },
+            n,
+        );
+        assert_eq!(pattern, rates_pattern(n));
+        // Generic code runs on array duals, and constant zeros are detected.
+        let pattern = jacobian_pattern(
+            |x: &[Dual<[Pattern; 4], RW, Pattern>]| {
+                vec![x[0] * x[1] + x[2] * Pattern(false), x[2].cos(), x[0] - x[0]]
+            },
+            3,
+        );
+        assert_eq!(pattern.row(0), &[0, 1]);
+        assert_eq!(pattern.row(1), &[2]);
+        assert_eq!(pattern.row(2), &[0]);
+        assert_eq!(
+            jacobian_pattern(|x: &[Dual<[Pattern; 5], RW, Pattern>]| rates(x), 4),
+            rates_pattern(4)
+        );
+    }
+
+    // Checks that adjacent columns have distinct colors, and that no path on four columns is bicolored.
+    fn check_star_coloring(pattern: &SparsityPattern, coloring: &ColumnColoring) {
+        let c = coloring.colors();
+        let neighbours = |v: usize| pattern.row(v).iter().copied().filter(move |&w| w != v);
+        for a in 0..pattern.ncols() {
+            for b in neighbours(a) {
+                assert_ne!(c[a], c[b]);
+                for x in neighbours(b).filter(|&x| x != a && c[x] == c[a]) {
+                    for y in neighbours(x).filter(|&y| y != a && y != b) {
+                        assert_ne!(c[y], c[b], "path {} {} {} {} is bicolored", a, b, x, y);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_star_coloring() {
+        let n = 40;
+        // Two bands and a few long range couplings
+        let rows = (0..n)
+            .map(|i: usize| {
+                let mut row: Vec<usize> = (i.saturating_sub(2)..(i + 3).min(n)).collect();
+                row.extend([(i * 7) % n, (i * 13) % n, (i * 29 % n * 7) % n].iter());
+                row
+            })
+            .collect();
+        let pattern = SparsityPattern::new(n, rows);
+        // Symmetrize
+        let mut entries = Vec::new();
+        for i in 0..n {
+            for &j in pattern.row(i) {
+                entries.push((i, j));
+                entries.push((j, i));
+            }
+        }
+        let pattern = SparsityPattern::from_entries(n, n, &entries);
+        let coloring = pattern.star_coloring();
+        check_star_coloring(&pattern, &coloring);
+        assert!(coloring.ncolors() < n);
+
+        let band = SparsityPattern::new(
+            n,
+            (0..n)
+                .map(|i: usize| (i.saturating_sub(1)..(i + 2).min(n)).collect())
+                .collect(),
+        );
+        let coloring = band.star_coloring();
+        check_star_coloring(&band, &coloring);
+        assert_eq!(coloring.ncolors(), 3);
+    }
+
+    #[test]
+    fn test_sparse_jacobian() {
+        let n = 30;
+        let x: Vec<f64> = (0..n).map(|i| 0.1 * i as f64 - 1.).collect();
+        let pattern = rates_pattern(n);
+        let coloring = pattern.column_coloring();
+        let (val, jac): (Vec<f64>, SparseJacobian<f64>) = sparse_jacobian(
+            |x: &[Dual<[f64; 5], RW, f64>]| rates(x),
+            &x,
+            &pattern,
+            &coloring,
+        );
+        assert_eq!(jac.nrows(), n);
+        assert_eq!(jac.ncols(), n);
+        assert_eq!(jac.values().len(), pattern.nnz());
+        let (eval, ejac): (Vec<f64>, Vec<f64>) =
+            jacobian(|x: &[Dual<[f64; 31], RW, f64>]| rates(x), &x);
+        assert_eq!(val, eval);
+        assert_eq!(jac.to_dense::<Vec<f64>>(), ejac);
+        for (i, j, v) in jac.iter() {
+            assert_eq!(v, ejac[i * n + j]);
+            assert_eq!(v, jac.get(i, j));
+        }
+    }
+}
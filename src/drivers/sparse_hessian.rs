@@ -0,0 +1,368 @@
+use super::{ColumnColoring, SparsityPattern};
+use crate::traits::{FromScalar, Zeros};
+use crate::{Dual, Pattern, Scalar, SparseDual, RW};
+use num_traits::One;
+
+/// A symmetric Hessian stored as the (row, column, value) triplets of its lower triangle, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseHessian<F> {
+    n: usize,
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    values: Vec<F>,
+}
+
+impl<F: Scalar> SparseHessian<F> {
+    /// Returns the number of rows and columns, that is of inputs.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the row indices of the entries.
+    pub fn rows(&self) -> &[usize] {
+        &self.rows
+    }
+
+    /// Returns the column indices of the entries, which are never greater than their row indices.
+    pub fn cols(&self) -> &[usize] {
+        &self.cols
+    }
+
+    /// Returns the values of the entries.
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    /// Returns the entry (i, j), or equivalently (j, i), which is zero outside of the pattern.
+    pub fn get(&self, i: usize, j: usize) -> F {
+        let (i, j) = if i >= j { (i, j) } else { (j, i) };
+        let start = self.rows.partition_point(|&r| r < i);
+        let end = self.rows.partition_point(|&r| r <= i);
+        match self.cols[start..end].binary_search(&j) {
+            Ok(k) => self.values[start + k],
+            Err(_) => F::zero(),
+        }
+    }
+
+    /// Iterates over the entries of the lower triangle, as (row, column, value).
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, F)> + '_ {
+        self.rows
+            .iter()
+            .zip(&self.cols)
+            .zip(&self.values)
+            .map(|((&i, &j), &v)| (i, j, v))
+    }
+
+    /// Returns the full Hessian as a dense row-major matrix.
+    pub fn to_dense<H: Zeros<F>>(&self) -> H {
+        let mut dense = H::zeros(self.n * self.n);
+        for (i, j, v) in self.iter() {
+            dense.rw()[i * self.n + j] = v;
+            dense.rw()[j * self.n + i] = v;
+        }
+        dense
+    }
+}
+
+// Evaluates `f` at `x`, the inputs being sparse duals over themselves, of duals over the groups of the inputs.
+// For each component i of the gradient of `f`, calls `visit(i, g, d)` with `d` its derivative along the group g,
+// that is along the sum of the inputs of this group.
+// The groups are handled as many at a time as the inner duals hold derivatives,
+// and the output of the first evaluation is returned.
+fn hessian_products<F, C, Fun, Vis>(
+    f: &Fun,
+    x: &[F],
+    groups: &[usize],
+    ngroups: usize,
+    mut visit: Vis,
+) -> SparseDual<Dual<C, RW, F>>
+where
+    F: Scalar,
+    C: FromScalar<F>,
+    Dual<C, RW, F>: Scalar,
+    Fun: Fn(&[SparseDual<Dual<C, RW, F>>]) -> SparseDual<Dual<C, RW, F>>,
+    Vis: FnMut(usize, usize, F),
+{
+    let chunk = Dual::<C, RW, F>::from(C::from_scalar(F::zero())).ndiffs();
+    assert!(
+        chunk > 0,
+        "The inner duals must hold at least one derivative"
+    );
+    let mut first = None;
+    for start in (0..ngroups.max(1)).step_by(chunk) {
+        let inputs: Vec<SparseDual<Dual<C, RW, F>>> = x
+            .iter()
+            .zip(groups)
+            .enumerate()
+            .map(|(i, (&xi, &g))| {
+                let mut inner = Dual::<C, RW, F>::from(C::from_scalar(xi));
+                if (start..start + chunk).contains(&g) {
+                    inner.diffs_mut()[g - start] = F::one();
+                }
+                SparseDual::from_pairs(inner, vec![(i, Dual::<C, RW, F>::one())])
+            })
+            .collect();
+        let out = f(&inputs);
+        for (i, d) in out.iter() {
+            for (k, &dk) in d.diffs().iter().enumerate() {
+                if start + k < ngroups {
+                    visit(i, start + k, dk);
+                }
+            }
+        }
+        first.get_or_insert(out);
+    }
+    first.unwrap()
+}
+
+/// Returns the sparsity pattern of the Hessian of `f`, a function of `n` inputs.
+///
+/// The inputs passed to `f` are `SparseDual`s over the inputs, of array-backed `Dual`s of
+/// [`Pattern`](../struct.Pattern.html)s. `f` is evaluated once for each group of as many inputs as
+/// these duals hold derivatives, so that larger arrays mean fewer evaluations.
+/// The pattern is symmetric, and valid for all inputs as long as `f` does not branch on their values.
+///
+/// # Cost
+///
+/// With N derivatives in the inner duals, `f` is evaluated ceil(n / N) times,
+/// each evaluation propagating index sets as large as the number of inputs each intermediate depends on.
+/// This is as many evaluations as a dense Hessian with the same inner duals, so the pattern should be
+/// computed once and reused for all the calls to [`sparse_hessian`] at different inputs.
+/// When the structure of `f` is known, as for partially separable objectives,
+/// the pattern can be built directly with `SparsityPattern::new` instead.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::hessian_pattern;
+/// use fwd_ad::traits::Scalar;
+///
+/// // A separable objective: sum of (x_i x_{i+1})²
+/// fn f<T: Scalar>(x: &[SparseDual<T>]) -> SparseDual<T> {
+///     x.windows(2)
+///         .map(|w| (w[0].clone() * &w[1]).powi(2))
+///         .fold(SparseDual::constant(T::zero()), |acc, t| acc + t)
+/// }
+///
+/// let pattern = hessian_pattern(|x: &[SparseDual<Dual<[Pattern; 3], RW, Pattern>>]| f(x), 5);
+/// assert_eq!(pattern.row(0), &[0, 1]);
+/// assert_eq!(pattern.row(2), &[1, 2, 3]);
+/// ```
+pub fn hessian_pattern<C, Fun>(f: Fun, n: usize) -> SparsityPattern
+where
+    C: FromScalar<Pattern>,
+    Dual<C, RW, Pattern>: Scalar,
+    Fun: Fn(&[SparseDual<Dual<C, RW, Pattern>>]) -> SparseDual<Dual<C, RW, Pattern>>,
+{
+    let groups: Vec<usize> = (0..n).collect();
+    let mut rows = vec![Vec::new(); n];
+    hessian_products(&f, &vec![Pattern(true); n], &groups, n, |i, j, d| {
+        if d.is_nonzero() {
+            rows[i].push(j);
+            rows[j].push(i);
+        }
+    });
+    SparsityPattern::new(n, rows)
+}
+
+/// Returns the gradient and the sparse Hessian of `f` at `x`, given the Hessian sparsity pattern and a coloring.
+///
+/// The pattern can be detected by [`hessian_pattern`], or built by hand.
+///
+/// The coloring must be a star coloring of the pattern, as computed by `SparsityPattern::star_coloring`,
+/// or a column coloring, which uses more colors.
+/// The products of the Hessian with the seeds of the colors are computed by second order forward propagation:
+/// the inputs passed to `f` are `SparseDual`s, carrying the gradient, of array-backed `Dual`s along the colors.
+/// `f` is evaluated once for each group of as many colors as these duals hold derivatives,
+/// and the entries of the Hessian are then read directly from the products.
+/// Entries outside of the pattern are assumed to be zero, and are not checked.
+///
+/// # Panics
+///
+/// Panics if the sizes of `x`, `pattern` and `coloring` do not match.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use fwd_ad::drivers::{hessian_pattern, sparse_hessian, SparsityPattern};
+/// use fwd_ad::traits::Scalar;
+///
+/// // A chained Rosenbrock function, whose Hessian is tridiagonal
+/// fn f<T: Scalar>(x: &[SparseDual<T>]) -> SparseDual<T> {
+///     let hundred = T::from(100.).unwrap();
+///     let mut res = SparseDual::constant(T::zero());
+///     for w in x.windows(2) {
+///         res += (w[1].clone() - w[0].clone().powi(2)).powi(2) * hundred;
+///         res += (-w[0].clone() + T::one()).powi(2);
+///     }
+///     res
+/// }
+///
+/// let n = 100;
+/// let pattern = hessian_pattern(|x: &[SparseDual<Dual<[Pattern; 9], RW, Pattern>>]| f(x), n);
+/// // The same pattern, written by hand
+/// let rows = (0..n).map(|i| (i.max(1) - 1..(i + 2).min(n)).collect()).collect();
+/// assert_eq!(pattern, SparsityPattern::new(n, rows));
+/// let coloring = pattern.star_coloring();
+/// assert_eq!(coloring.ncolors(), 3);
+/// let g = |x: &[SparseDual<Dual<[f64; 4], RW, f64>>]| f(x);
+/// let (grad, hess): (Vec<f64>, _) = sparse_hessian(g, &vec![1.; n], &pattern, &coloring);
+/// assert_eq!(grad, vec![0.; n]);
+/// assert_eq!(hess.nnz(), 2 * n - 1);
+/// assert_eq!(hess.get(0, 0), 802.);
+/// assert_eq!(hess.get(1, 1), 1002.);
+/// assert_eq!(hess.get(0, 1), -400.);
+/// ```
+pub fn sparse_hessian<F, C, G, Fun>(
+    f: Fun,
+    x: &[F],
+    pattern: &SparsityPattern,
+    coloring: &ColumnColoring,
+) -> (G, SparseHessian<F>)
+where
+    F: Scalar,
+    C: FromScalar<F>,
+    Dual<C, RW, F>: Scalar,
+    G: Zeros<F>,
+    Fun: Fn(&[SparseDual<Dual<C, RW, F>>]) -> SparseDual<Dual<C, RW, F>>,
+{
+    let n = x.len();
+    assert!(
+        pattern.nrows() == n && pattern.ncols() == n,
+        "sparse_hessian: the pattern must have one row and one column per input"
+    );
+    assert_eq!(
+        coloring.colors().len(),
+        n,
+        "sparse_hessian: the coloring must have one color per input"
+    );
+    let colors = coloring.colors();
+    let p = coloring.ncolors();
+    // products[i * p + c] is the component i of the product of the Hessian with the seed of the color c
+    let mut products = vec![F::zero(); n * p];
+    let out = hessian_products(&f, x, colors, p, |i, c, d| products[i * p + c] = d);
+    let mut grad = G::zeros(n);
+    for (i, d) in out.iter() {
+        grad.rw()[i] = d.val();
+    }
+
+    let mut hess = SparseHessian {
+        n,
+        rows: Vec::new(),
+        cols: Vec::new(),
+        values: Vec::new(),
+    };
+    // count[c] is the number of neighbours of i of color c
+    let mut count = vec![0; p];
+    for i in 0..n {
+        let row = pattern.row(i);
+        for &j in row.iter().filter(|&&j| j != i) {
+            count[colors[j]] += 1;
+        }
+        for &j in row.iter().filter(|&&j| j <= i) {
+            // If j is the only neighbour of i of its color, the product with this color holds (i, j) at i,
+            // otherwise i is the only neighbour of j of its color.
+            let v = if j == i || count[colors[j]] == 1 {
+                products[i * p + colors[j]]
+            } else {
+                products[j * p + colors[i]]
+            };
+            hess.rows.push(i);
+            hess.cols.push(j);
+            hess.values.push(v);
+        }
+        for &j in row {
+            count[colors[j]] = 0;
+        }
+    }
+    (grad, hess)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::{hessian, packed_index, Dual2};
+
+    fn dense<T: Scalar>(x: &[T]) -> T {
+        let n = x.len();
+        let mut res = x[0] * x[n - 1].sin() + (x[2] / x[4]).exp();
+        for i in 0..n - 1 {
+            res += (x[i + 1] - x[i] * x[i]).powi(2) + x[i].cos();
+        }
+        res
+    }
+
+    fn sparse<T: Scalar>(x: &[SparseDual<T>]) -> SparseDual<T> {
+        let n = x.len();
+        let mut res = x[0].clone() * x[n - 1].clone().sin() + (x[2].clone() / &x[4]).exp();
+        for i in 0..n - 1 {
+            res += (x[i + 1].clone() - x[i].clone() * &x[i]).powi(2) + x[i].clone().cos();
+        }
+        res
+    }
+
+    #[test]
+    fn test_hessian_pattern() {
+        let n = 7;
+        let expected: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| vec![(i, i), (i, (i + 1).min(n - 1))])
+            .chain(vec![(0, n - 1), (2, 4)])
+            .flat_map(|(i, j)| vec![(i, j), (j, i)])
+            .collect();
+        let expected = SparsityPattern::from_entries(n, n, &expected);
+        let pattern = hessian_pattern(
+            |x: &[SparseDual<Dual<[Pattern; 3], RW, Pattern>>]| sparse(x),
+            n,
+        );
+        assert_eq!(pattern, expected);
+        let pattern = hessian_pattern(
+            |x: &[SparseDual<Dual<[Pattern; 9], RW, Pattern>>]| sparse(x),
+            n,
+        );
+        assert_eq!(pattern, expected);
+    }
+
+    #[test]
+    fn test_sparse_hessian() {
+        let x = [0.3, -1.2, 0.8, 0.5, 1.5, -0.7, 2.];
+        let n = x.len();
+        let (egrad, ehess): (Vec<f64>, Vec<f64>) = hessian(|x: &[Dual2<f64>]| dense(x), &x);
+        let pattern = hessian_pattern(
+            |x: &[SparseDual<Dual<[Pattern; 8], RW, Pattern>>]| sparse(x),
+            n,
+        );
+        for coloring in [pattern.star_coloring(), pattern.column_coloring()].iter() {
+            // One color at a time, then all of them at once
+            let (grad, hess): (Vec<f64>, SparseHessian<f64>) = sparse_hessian(
+                |x: &[SparseDual<Dual<[f64; 2], RW, f64>>]| sparse(x),
+                &x,
+                &pattern,
+                coloring,
+            );
+            let (grad2, hess2): (Vec<f64>, SparseHessian<f64>) = sparse_hessian(
+                |x: &[SparseDual<Dual<[f64; 8], RW, f64>>]| sparse(x),
+                &x,
+                &pattern,
+                coloring,
+            );
+            assert_eq!(grad, grad2);
+            assert_eq!(hess, hess2);
+            assert_eq!(hess.nnz(), (pattern.nnz() + n) / 2);
+            for (g, eg) in grad.iter().zip(&egrad) {
+                assert!((g - eg).abs() < 1e-12);
+            }
+            for i in 0..n {
+                for j in 0..n {
+                    let expected = ehess[packed_index(i, j)];
+                    assert!((hess.get(i, j) - expected).abs() < 1e-12, "({}, {})", i, j);
+                }
+            }
+            let full: Vec<f64> = hess.to_dense();
+            assert_eq!(full[n + 2], hess.get(2, 1));
+            assert_eq!(full[2 * n + 1], hess.get(2, 1));
+        }
+    }
+}
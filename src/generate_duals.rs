@@ -2,6 +2,9 @@
 ///
 ///  Can optionally generate a "getter" closure used to get the derivative *with respect* to the variable.
 ///
+/// To seed the duals with arbitrary tangents instead, see [`jvp`](drivers/fn.jvp.html) and
+/// [`jvp_seeded`](drivers/fn.jvp_seeded.html).
+///
 /// # Examples
 ///
 /// ```
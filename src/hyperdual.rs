@@ -0,0 +1,708 @@
+//! A module containing hyper-dual numbers, used to compute exact second derivatives.
+//!
+//! A hyper-dual number is `v + a ε1 + b ε2 + c ε1ε2`, where ε1² = ε2² = 0 but ε1ε2 ≠ 0.
+//! Evaluating a function `f` at `x + ε1 + ε2` gives `f''(x)` as the ε1ε2 part,
+//! and evaluating a function of two variables at `x + ε1` and `y + ε2` gives their mixed second derivative.
+//!
+//! ```
+//! # use fwd_ad::*;
+//! // d²/dxdy (x * y.sin()) = y.cos()
+//! let x = HyperDual::<_, RW, f64>::from([2., 1., 0., 0.]);
+//! let y = HyperDual::<_, RW, f64>::from([0.5, 0., 1., 0.]);
+//! let res = x * y.sin();
+//! assert_eq!(res.eps1(), 0.5_f64.sin());
+//! assert_eq!(res.eps2(), 2. * 0.5_f64.cos());
+//! assert_eq!(res.eps12(), 0.5_f64.cos());
+//! ```
+
+use super::{CompatibleWith, OwningMode, ROAble, RWAble, Scalar, ToOwning, ToView, RO, RW};
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use std::ops;
+
+/// The struct implementing hyper-dual numbers.
+///
+/// As `Dual`, it is parametrized by a container type `T`, an owning mode `M` and a scalar type `F`.
+/// The container must hold exactly four scalars, which are, in order,
+/// the value, the ε1 part, the ε2 part and the ε1ε2 part.
+///
+/// A `constant` method is provided for RW `HyperDual`s backed by a `Vec` or a `[F; 4]`.
+#[derive(PartialEq, Debug, Clone, Copy, Hash, Default)]
+pub struct HyperDual<T, M, F>
+where
+    M: OwningMode,
+    T: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    content: T,
+    om: M,
+    ph_f: PhantomData<F>,
+}
+
+/// # Panics
+///
+/// Panics if the container does not hold exactly four scalars.
+impl<T, M, F> From<T> for HyperDual<T, M, F>
+where
+    M: OwningMode,
+    T: ROAble<F>,
+    T: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn from(x: T) -> Self {
+        assert_eq!(
+            x.ro().len(),
+            4,
+            "A HyperDual must hold exactly four scalars, not {}.",
+            x.ro().len()
+        );
+        HyperDual {
+            content: x,
+            om: M::default(),
+            ph_f: PhantomData,
+        }
+    }
+}
+
+/// As for `Dual`s, only the values are ordered.
+impl<T, M, F> PartialOrd for HyperDual<T, M, F>
+where
+    M: OwningMode + PartialEq,
+    T: ROAble<F> + CompatibleWith<M, F> + PartialEq,
+    F: Scalar,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.val().partial_cmp(&other.val())
+    }
+}
+
+impl<F> HyperDual<Vec<F>, RW, F>
+where
+    F: Scalar,
+{
+    /// Generates a hyper-dual number backed by a Vec<F> with value `value` and null ε parts.
+    pub fn constant(value: F) -> Self {
+        HyperDual::from(vec![value, F::zero(), F::zero(), F::zero()])
+    }
+}
+
+impl<F> HyperDual<[F; 4], RW, F>
+where
+    F: Scalar,
+{
+    /// Generates a hyper-dual number backed by an array with value `value` and null ε parts.
+    pub fn constant(value: F) -> Self {
+        HyperDual::from([value, F::zero(), F::zero(), F::zero()])
+    }
+}
+
+/// Implementations for HyperDuals that do not necessarily own their content.
+impl<T, M, F> HyperDual<T, M, F>
+where
+    M: OwningMode,
+    T: ROAble<F>,
+    T: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    /// Clone the borrowed content, so that the resulting HyperDual
+    /// owns its content.
+    pub fn to_owning(&self) -> HyperDual<T::Owning, RW, F>
+    where
+        T: ToOwning<F>,
+    {
+        HyperDual::from(self.content.to_owning())
+    }
+
+    /// Returns the content as a slice.
+    pub fn as_slice(&self) -> &[F] {
+        self.content.ro()
+    }
+
+    /// Returns the value of the hyper-dual.
+    pub fn val(&self) -> F {
+        self.as_slice()[0]
+    }
+
+    /// Returns the ε1 part, that is the first derivative along the first direction.
+    pub fn eps1(&self) -> F {
+        self.as_slice()[1]
+    }
+
+    /// Returns the ε2 part, that is the first derivative along the second direction.
+    pub fn eps2(&self) -> F {
+        self.as_slice()[2]
+    }
+
+    /// Returns the ε1ε2 part, that is the second derivative along both directions.
+    pub fn eps12(&self) -> F {
+        self.as_slice()[3]
+    }
+
+    /// Allows comparing to hyper-duals by checking whether they are elementwise within `atol` of each other.
+    pub fn is_close<S, M2>(&self, b: &HyperDual<S, M2, F>, atol: F) -> bool
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        self.as_slice()
+            .iter()
+            .zip(b.as_slice())
+            .all(|(xs, xb)| (*xs - *xb).abs() <= atol)
+    }
+
+    /// Returns a non-owning HyperDual backed by the ViewType of self.
+    pub fn view<'a>(&'a self) -> HyperDual<&'a T::ViewType, RO, F>
+    where
+        T: ToView<F>,
+        &'a T::ViewType: CompatibleWith<RO, F>,
+    {
+        // The view holds the four scalars of self, there is nothing to check
+        HyperDual {
+            content: self.content.view(),
+            om: RO,
+            ph_f: PhantomData,
+        }
+    }
+
+    /// Consumes the `HyperDual` and return the container inside it.
+    pub fn into_container(self) -> T {
+        self.content
+    }
+}
+
+/// Methods for HyperDuals that own their content
+impl<T, F> HyperDual<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    /// Returns the content a mutable slice.
+    pub fn as_slice_mut(&mut self) -> &mut [F] {
+        self.content.rw()
+    }
+
+    /// Return a mutable reference to the value.
+    pub fn val_mut(&mut self) -> &mut F {
+        &mut self.as_slice_mut()[0]
+    }
+
+    /// Return a mutable reference to the ε1 part.
+    pub fn eps1_mut(&mut self) -> &mut F {
+        &mut self.as_slice_mut()[1]
+    }
+
+    /// Return a mutable reference to the ε2 part.
+    pub fn eps2_mut(&mut self) -> &mut F {
+        &mut self.as_slice_mut()[2]
+    }
+
+    /// Return a mutable reference to the ε1ε2 part.
+    pub fn eps12_mut(&mut self) -> &mut F {
+        &mut self.as_slice_mut()[3]
+    }
+
+    // Applies a function, given its value `f0`, first derivative `f1` and second derivative `f2` at self.
+    fn chain(mut self, f0: F, f1: F, f2: F) -> Self {
+        let s = self.as_slice_mut();
+        let (a, b) = (s[1], s[2]);
+        s[0] = f0;
+        s[1] = f1 * a;
+        s[2] = f1 * b;
+        s[3] = f1 * s[3] + f2 * a * b;
+        self
+    }
+
+    /// Returns e^self.
+    pub fn exp(self) -> Self {
+        let e = self.val().exp();
+        self.chain(e, e, e)
+    }
+
+    /// Returns 2^self.
+    pub fn exp2(self) -> Self {
+        let e = self.val().exp2();
+        let l = F::LN_2();
+        self.chain(e, l * e, l * l * e)
+    }
+
+    /// Returns ln(self).
+    pub fn ln(self) -> Self {
+        let v = self.val();
+        let r = v.recip();
+        self.chain(v.ln(), r, -r * r)
+    }
+
+    /// Returns 1/self.
+    pub fn inv(self) -> Self {
+        let r = self.val().recip();
+        self.chain(r, -r * r, (r + r) * r * r)
+    }
+
+    /// Returns self^exp.
+    pub fn powf(self, exp: F) -> Self {
+        let v = self.val();
+        let e1 = exp - F::one();
+        self.chain(
+            v.powf(exp),
+            exp * v.powf(e1),
+            exp * e1 * v.powf(e1 - F::one()),
+        )
+    }
+
+    /// Returns self^n.
+    pub fn powi(self, n: i32) -> Self {
+        let v = self.val();
+        let nf = F::from(n).unwrap();
+        let (f1, f2) = match n {
+            0 => (F::zero(), F::zero()),
+            1 => (F::one(), F::zero()),
+            _ => (nf * v.powi(n - 1), nf * (nf - F::one()) * v.powi(n - 2)),
+        };
+        self.chain(v.powi(n), f1, f2)
+    }
+
+    /// Returns sqrt(self).
+    pub fn sqrt(self) -> Self {
+        let s = self.val().sqrt();
+        let d = (s + s).recip();
+        self.chain(s, d, -d * d * d * (F::one() + F::one()))
+    }
+
+    /// Returns cbrt(self).
+    pub fn cbrt(self) -> Self {
+        let v = self.val();
+        let c = v.cbrt();
+        let three = F::one() + F::one() + F::one();
+        let d = (three * c * c).recip();
+        self.chain(c, d, -(three - F::one()) * d / (three * v))
+    }
+
+    /// Returns |self|.
+    ///
+    /// At 0, the derivatives of the positive branch are used, as for `Dual::abs`.
+    pub fn abs(self) -> Self {
+        if self.val() < F::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Returns sin(self).
+    pub fn sin(self) -> Self {
+        let (s, c) = self.val().sin_cos();
+        self.chain(s, c, -s)
+    }
+
+    /// Returns cos(self).
+    pub fn cos(self) -> Self {
+        let (s, c) = self.val().sin_cos();
+        self.chain(c, -s, -c)
+    }
+
+    /// Returns tan(self).
+    pub fn tan(self) -> Self {
+        let t = self.val().tan();
+        let d = F::one() + t * t;
+        self.chain(t, d, (t + t) * d)
+    }
+
+    /// Returns asin(self).
+    pub fn asin(self) -> Self {
+        let v = self.val();
+        let d = (F::one() - v * v).sqrt().recip();
+        self.chain(v.asin(), d, v * d * d * d)
+    }
+
+    /// Returns acos(self).
+    pub fn acos(self) -> Self {
+        let v = self.val();
+        let d = (F::one() - v * v).sqrt().recip();
+        self.chain(v.acos(), -d, -v * d * d * d)
+    }
+
+    /// Returns atan(self).
+    pub fn atan(self) -> Self {
+        let v = self.val();
+        let d = (F::one() + v * v).recip();
+        self.chain(v.atan(), d, -(v + v) * d * d)
+    }
+
+    /// Returns sinh(self).
+    pub fn sinh(self) -> Self {
+        let v = self.val();
+        let s = v.sinh();
+        self.chain(s, v.cosh(), s)
+    }
+
+    /// Returns cosh(self).
+    pub fn cosh(self) -> Self {
+        let v = self.val();
+        let c = v.cosh();
+        self.chain(c, v.sinh(), c)
+    }
+
+    /// Returns tanh(self).
+    pub fn tanh(self) -> Self {
+        let t = self.val().tanh();
+        let d = F::one() - t * t;
+        self.chain(t, d, -(t + t) * d)
+    }
+
+    /// Returns asinh(self).
+    pub fn asinh(self) -> Self {
+        let v = self.val();
+        let d = (v * v + F::one()).sqrt().recip();
+        self.chain(v.asinh(), d, -v * d * d * d)
+    }
+
+    /// Returns acosh(self).
+    pub fn acosh(self) -> Self {
+        let v = self.val();
+        let d = (v * v - F::one()).sqrt().recip();
+        self.chain(v.acosh(), d, -v * d * d * d)
+    }
+
+    /// Returns atanh(self).
+    pub fn atanh(self) -> Self {
+        let v = self.val();
+        let d = (F::one() - v * v).recip();
+        self.chain(v.atanh(), d, (v + v) * d * d)
+    }
+}
+
+#[cfg(feature = "implicit-clone")]
+mod implicit_clone {
+    use super::*;
+
+    macro_rules! clone_impl {
+        {$fname: ident($($param : ident : $ptype : ty),*)} => {
+            pub fn $fname(&self,$($param : $ptype),*) -> HyperDual<T::Owning, RW, F> {
+                    let res = self.to_owning();
+                    res.$fname($($param),*)
+            }
+        }
+    }
+
+    impl<T, F> HyperDual<T, RO, F>
+    where
+        T: ToOwning<F>,
+        F: Scalar,
+    {
+        clone_impl!(exp());
+        clone_impl!(exp2());
+        clone_impl!(ln());
+        clone_impl!(inv());
+        clone_impl!(powf(exp: F));
+        clone_impl!(powi(n: i32));
+        clone_impl!(sqrt());
+        clone_impl!(cbrt());
+        clone_impl!(abs());
+        clone_impl!(sin());
+        clone_impl!(cos());
+        clone_impl!(tan());
+        clone_impl!(asin());
+        clone_impl!(acos());
+        clone_impl!(atan());
+        clone_impl!(sinh());
+        clone_impl!(cosh());
+        clone_impl!(tanh());
+        clone_impl!(asinh());
+        clone_impl!(acosh());
+        clone_impl!(atanh());
+    }
+}
+
+//
+//
+// Ops Implementations
+// ===================
+//
+//
+
+impl<L, R, M, F> ops::AddAssign<&HyperDual<R, M, F>> for HyperDual<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn add_assign(&mut self, rhs: &HyperDual<R, M, F>) {
+        self.as_slice_mut()
+            .iter_mut()
+            .zip(rhs.as_slice())
+            .for_each(|(xs, xr)| *xs += *xr);
+    }
+}
+
+impl<L, F> ops::AddAssign<F> for HyperDual<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn add_assign(&mut self, rhs: F) {
+        *self.val_mut() += rhs;
+    }
+}
+
+derive_ops_for!(HyperDual<T, M, F>: Add, AddAssign, add, add_assign);
+
+impl<L, R, M, F> ops::SubAssign<&HyperDual<R, M, F>> for HyperDual<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn sub_assign(&mut self, rhs: &HyperDual<R, M, F>) {
+        self.as_slice_mut()
+            .iter_mut()
+            .zip(rhs.as_slice())
+            .for_each(|(xs, xr)| *xs -= *xr);
+    }
+}
+
+impl<L, F> ops::SubAssign<F> for HyperDual<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn sub_assign(&mut self, rhs: F) {
+        *self.val_mut() -= rhs;
+    }
+}
+
+derive_ops_for!(HyperDual<T, M, F>: Sub, SubAssign, sub, sub_assign);
+
+impl<L, R, M, F> ops::MulAssign<&HyperDual<R, M, F>> for HyperDual<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn mul_assign(&mut self, rhs: &HyperDual<R, M, F>) {
+        let r = rhs.as_slice();
+        let s = self.as_slice_mut();
+        s[3] = s[0] * r[3] + s[1] * r[2] + s[2] * r[1] + s[3] * r[0];
+        s[2] = s[0] * r[2] + s[2] * r[0];
+        s[1] = s[0] * r[1] + s[1] * r[0];
+        s[0] *= r[0];
+    }
+}
+
+impl<L, F> ops::MulAssign<F> for HyperDual<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn mul_assign(&mut self, rhs: F) {
+        self.as_slice_mut().iter_mut().for_each(|xs| *xs *= rhs);
+    }
+}
+
+derive_ops_for!(HyperDual<T, M, F>: Mul, MulAssign, mul, mul_assign);
+
+impl<L, R, M, F> ops::DivAssign<&HyperDual<R, M, F>> for HyperDual<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn div_assign(&mut self, rhs: &HyperDual<R, M, F>) {
+        // Multiply by the inverse of rhs
+        let r = rhs.as_slice();
+        let i0 = r[0].recip();
+        let i1 = -r[1] * i0 * i0;
+        let i2 = -r[2] * i0 * i0;
+        let i3 = (r[1] * r[2] * (i0 + i0) - r[3]) * i0 * i0;
+        let s = self.as_slice_mut();
+        s[3] = s[0] * i3 + s[1] * i2 + s[2] * i1 + s[3] * i0;
+        s[2] = s[0] * i2 + s[2] * i0;
+        s[1] = s[0] * i1 + s[1] * i0;
+        s[0] *= i0;
+    }
+}
+
+impl<L, F> ops::DivAssign<F> for HyperDual<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn div_assign(&mut self, rhs: F) {
+        self.as_slice_mut().iter_mut().for_each(|xs| *xs /= rhs);
+    }
+}
+
+derive_ops_for!(HyperDual<T, M, F>: Div, DivAssign, div, div_assign);
+
+impl<T, F> ops::Neg for HyperDual<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    type Output = Self;
+    fn neg(mut self) -> Self {
+        self.as_slice_mut().iter_mut().for_each(|xs| *xs = -*xs);
+        self
+    }
+}
+
+#[cfg(feature = "implicit-clone")]
+impl<T, F> ops::Neg for HyperDual<T, RO, F>
+where
+    T: ToOwning<F>,
+    F: Scalar,
+{
+    type Output = HyperDual<T::Owning, RW, F>;
+    fn neg(self) -> Self::Output {
+        -self.to_owning()
+    }
+}
+
+scalar_lhs_ops_for!(HyperDual<T, M, F>: f32);
+scalar_lhs_ops_for!(HyperDual<T, M, F>: f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type HD = HyperDual<[f64; 4], RW, f64>;
+
+    // Evaluates f at x + ε1 + ε2, returning (f(x), f'(x), f''(x))
+    fn second<G: Fn(HD) -> HD>(f: G, x: f64) -> (f64, f64, f64) {
+        let res = f(HD::from([x, 1., 1., 0.]));
+        assert_eq!(res.eps1(), res.eps2());
+        (res.val(), res.eps1(), res.eps12())
+    }
+
+    fn check<G: Fn(HD) -> HD>(f: G, x: f64, expected: (f64, f64, f64)) {
+        let (f0, f1, f2) = second(f, x);
+        assert!((f0 - expected.0).abs() < 1e-13, "{} =/= {}", f0, expected.0);
+        assert!((f1 - expected.1).abs() < 1e-13, "{} =/= {}", f1, expected.1);
+        assert!((f2 - expected.2).abs() < 1e-13, "{} =/= {}", f2, expected.2);
+    }
+
+    #[test]
+    fn test_functions() {
+        let x = 0.3_f64;
+        check(|x| x.exp(), x, (x.exp(), x.exp(), x.exp()));
+        check(
+            |x| x.exp2(),
+            x,
+            (
+                x.exp2(),
+                2_f64.ln() * x.exp2(),
+                2_f64.ln().powi(2) * x.exp2(),
+            ),
+        );
+        check(|x| x.ln(), x, (x.ln(), 1. / x, -1. / (x * x)));
+        check(|x| x.inv(), x, (1. / x, -1. / (x * x), 2. / x.powi(3)));
+        check(
+            |x| x.powf(2.5),
+            x,
+            (x.powf(2.5), 2.5 * x.powf(1.5), 3.75 * x.powf(0.5)),
+        );
+        check(|x| x.powf(2.), 0., (0., 0., 2.));
+        check(|x| x.powf(3.), 0., (0., 0., 0.));
+        assert_eq!(second(|x| x.powf(1.5), 0.), (0., 0., f64::INFINITY));
+        check(|x| x.powi(3), x, (x.powi(3), 3. * x * x, 6. * x));
+        check(|x| x.powi(1), x, (x, 1., 0.));
+        check(
+            |x| x.sqrt(),
+            x,
+            (x.sqrt(), 0.5 / x.sqrt(), -0.25 / x.powf(1.5)),
+        );
+        check(
+            |x| x.cbrt(),
+            x,
+            (x.cbrt(), x.powf(-2. / 3.) / 3., -2. / 9. * x.powf(-5. / 3.)),
+        );
+        check(|x| x.sin(), x, (x.sin(), x.cos(), -x.sin()));
+        check(|x| x.cos(), x, (x.cos(), -x.sin(), -x.cos()));
+        let sec2 = 1. / x.cos().powi(2);
+        check(|x| x.tan(), x, (x.tan(), sec2, 2. * sec2 * x.tan()));
+        let s = 1. - x * x;
+        check(|x| x.asin(), x, (x.asin(), s.powf(-0.5), x * s.powf(-1.5)));
+        check(
+            |x| x.acos(),
+            x,
+            (x.acos(), -s.powf(-0.5), -x * s.powf(-1.5)),
+        );
+        check(
+            |x| x.atan(),
+            x,
+            (x.atan(), 1. / (1. + x * x), -2. * x / (1. + x * x).powi(2)),
+        );
+        check(|x| x.atanh(), x, (x.atanh(), 1. / s, 2. * x / (s * s)));
+        check(|x| x.sinh(), x, (x.sinh(), x.cosh(), x.sinh()));
+        check(|x| x.cosh(), x, (x.cosh(), x.sinh(), x.cosh()));
+        let t = x.tanh();
+        check(|x| x.tanh(), x, (t, 1. - t * t, -2. * t * (1. - t * t)));
+        let a = 1. + x * x;
+        check(
+            |x| x.asinh(),
+            x,
+            (x.asinh(), a.powf(-0.5), -x * a.powf(-1.5)),
+        );
+        let y = 1.7_f64;
+        let c = y * y - 1.;
+        check(
+            |x| x.acosh(),
+            y,
+            (y.acosh(), c.powf(-0.5), -y * c.powf(-1.5)),
+        );
+        check(|x| -x.abs(), -y, (-y, 1., 0.));
+    }
+
+    #[test]
+    fn test_ops() {
+        let x = 0.7_f64;
+        check(
+            |x| x * x.view() * x.view(),
+            x,
+            (x.powi(3), 3. * x * x, 6. * x),
+        );
+        check(|x| 1. / x, x, (1. / x, -1. / (x * x), 2. / x.powi(3)));
+        check(|x| x.sin() / x.cos(), x, second(|x| x.tan(), x));
+        check(|x| 2. - x * 3. + 1., x, (3. - 3. * x, -3., 0.));
+        check(|x| (x - 2.) / 4., x, ((x - 2.) / 4., 0.25, 0.));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let x = HyperDual::<_, RW, f64>::from([1., 1., 0., 0.]);
+        let y = HyperDual::<_, RW, f64>::from([1., -5., 0., 0.]);
+        assert_eq!(x.partial_cmp(&y), Some(Ordering::Equal));
+        assert!(HyperDual::<[f64; 4], RW, f64>::constant(0.5) < y);
+    }
+
+    #[test]
+    #[should_panic(expected = "A HyperDual must hold exactly four scalars, not 3.")]
+    fn test_wrong_len() {
+        let _ = HyperDual::<Vec<f64>, RW, f64>::from(vec![1., 1., 0.]);
+    }
+
+    #[test]
+    fn test_mixed() {
+        // f(x, y) = exp(xy) / (1 + x²) + sin(x) ln(y)
+        let (x0, y0) = (0.5_f64, 1.5_f64);
+        let x = HD::from([x0, 1., 0., 0.]);
+        let y = HyperDual::<Vec<f64>, RW, f64>::from(vec![y0, 0., 1., 0.]);
+        let res = (x * y.view()).exp() / (x.powi(2) + 1.) + x.sin() * y.ln();
+        let q = 1. + x0 * x0;
+        let e = (x0 * y0).exp();
+        let dx = e * (y0 / q - 2. * x0 / (q * q)) + x0.cos() * y0.ln();
+        let dy = e * x0 / q + x0.sin() / y0;
+        let dxy = e * (x0 * (y0 / q - 2. * x0 / (q * q)) + 1. / q) + x0.cos() / y0;
+        assert!((res.eps1() - dx).abs() < 1e-13);
+        assert!((res.eps2() - dy).abs() < 1e-13);
+        assert!((res.eps12() - dxy).abs() < 1e-13);
+    }
+}
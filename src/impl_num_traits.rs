@@ -0,0 +1,540 @@
+use crate::traits::FromScalar;
+use crate::{Dual, KinkPolicy, Scalar, ToOwning, RW};
+use num_traits::{Float, FloatConst, FromPrimitive, Num, NumCast, One, Signed, ToPrimitive, Zero};
+use std::num::FpCategory;
+
+// A constant dual, with as many diffs as the container holds.
+fn scalar<T, F>(value: F) -> Dual<T, RW, F>
+where
+    T: FromScalar<F>,
+    F: Scalar,
+{
+    From::from(T::from_scalar(value))
+}
+
+//
+//
+// Constants
+// =========
+//
+//
+
+impl<T, F> Zero for Dual<T, RW, F>
+where
+    T: FromScalar<F>,
+    F: Scalar,
+{
+    fn zero() -> Self {
+        scalar(F::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.as_slice().iter().all(F::is_zero)
+    }
+}
+
+impl<T, F> One for Dual<T, RW, F>
+where
+    T: FromScalar<F>,
+    F: Scalar,
+{
+    fn one() -> Self {
+        scalar(F::one())
+    }
+}
+
+impl<T, F> Num for Dual<T, RW, F>
+where
+    T: FromScalar<F> + PartialEq,
+    F: Scalar,
+{
+    type FromStrRadixErr = F::FromStrRadixErr;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        F::from_str_radix(str, radix).map(scalar)
+    }
+}
+
+macro_rules! float_consts {
+    ($($name : ident),*) => {
+        impl<T, F> FloatConst for Dual<T, RW, F>
+        where
+            T: FromScalar<F>,
+            F: Scalar,
+        {
+            $(
+                fn $name() -> Self {
+                    scalar(F::$name())
+                }
+            )*
+        }
+    };
+}
+
+float_consts!(
+    E,
+    FRAC_1_PI,
+    FRAC_1_SQRT_2,
+    FRAC_2_PI,
+    FRAC_2_SQRT_PI,
+    FRAC_PI_2,
+    FRAC_PI_3,
+    FRAC_PI_4,
+    FRAC_PI_6,
+    FRAC_PI_8,
+    LN_10,
+    LN_2,
+    LOG10_E,
+    LOG2_E,
+    PI,
+    SQRT_2
+);
+
+//
+//
+// Conversions
+// ===========
+//
+//
+
+// Conversions to primitives only look at the value.
+impl<T, F> ToPrimitive for Dual<T, RW, F>
+where
+    T: FromScalar<F>,
+    F: Scalar,
+{
+    fn to_i64(&self) -> Option<i64> {
+        self.val().to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.val().to_u64()
+    }
+
+    fn to_f32(&self) -> Option<f32> {
+        self.val().to_f32()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.val().to_f64()
+    }
+}
+
+impl<T, F> NumCast for Dual<T, RW, F>
+where
+    T: FromScalar<F>,
+    F: Scalar,
+{
+    fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+        <F as NumCast>::from(n).map(scalar)
+    }
+}
+
+impl<T, F> FromPrimitive for Dual<T, RW, F>
+where
+    T: FromScalar<F>,
+    F: Scalar,
+{
+    fn from_i64(n: i64) -> Option<Self> {
+        <F as NumCast>::from(n).map(scalar)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        <F as NumCast>::from(n).map(scalar)
+    }
+
+    fn from_f32(n: f32) -> Option<Self> {
+        <F as NumCast>::from(n).map(scalar)
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        <F as NumCast>::from(n).map(scalar)
+    }
+}
+
+//
+//
+// Signed
+// ======
+//
+//
+
+impl<T, F> Signed for Dual<T, RW, F>
+where
+    T: FromScalar<F> + ToOwning<F, Owning = T> + PartialEq,
+    F: Scalar,
+{
+    fn abs(&self) -> Self {
+        self.to_owning().abs()
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        let diff = self.to_owning() - other;
+        if diff.val() > F::zero() {
+            diff
+        } else {
+            diff.chain(F::zero(), F::zero())
+        }
+    }
+
+    fn signum(&self) -> Self {
+        self.to_owning().signum_with(KinkPolicy::Right)
+    }
+
+    fn is_positive(&self) -> bool {
+        self.val().is_sign_positive()
+    }
+
+    fn is_negative(&self) -> bool {
+        self.val().is_sign_negative()
+    }
+}
+
+//
+//
+// Float
+// =====
+//
+//
+
+// Predicates only look at the value, and kinks use the `KinkPolicy::Right` convention, as `abs`.
+impl<T, F> Float for Dual<T, RW, F>
+where
+    T: FromScalar<F> + ToOwning<F, Owning = T> + Copy + PartialEq,
+    F: Scalar + Float,
+{
+    fn nan() -> Self {
+        scalar(<F as Float>::nan())
+    }
+
+    fn infinity() -> Self {
+        scalar(<F as Float>::infinity())
+    }
+
+    fn neg_infinity() -> Self {
+        scalar(<F as Float>::neg_infinity())
+    }
+
+    fn neg_zero() -> Self {
+        scalar(<F as Float>::neg_zero())
+    }
+
+    fn min_value() -> Self {
+        scalar(<F as Float>::min_value())
+    }
+
+    fn min_positive_value() -> Self {
+        scalar(<F as Float>::min_positive_value())
+    }
+
+    fn max_value() -> Self {
+        scalar(<F as Float>::max_value())
+    }
+
+    fn epsilon() -> Self {
+        scalar(<F as Float>::epsilon())
+    }
+
+    fn is_nan(self) -> bool {
+        Float::is_nan(self.val())
+    }
+
+    fn is_infinite(self) -> bool {
+        Float::is_infinite(self.val())
+    }
+
+    fn is_finite(self) -> bool {
+        Float::is_finite(self.val())
+    }
+
+    fn is_normal(self) -> bool {
+        Float::is_normal(self.val())
+    }
+
+    fn classify(self) -> FpCategory {
+        Float::classify(self.val())
+    }
+
+    fn is_sign_positive(self) -> bool {
+        Float::is_sign_positive(self.val())
+    }
+
+    fn is_sign_negative(self) -> bool {
+        Float::is_sign_negative(self.val())
+    }
+
+    fn integer_decode(self) -> (u64, i16, i8) {
+        Float::integer_decode(self.val())
+    }
+
+    fn floor(self) -> Self {
+        self.floor_with(KinkPolicy::Right)
+    }
+
+    fn ceil(self) -> Self {
+        self.ceil_with(KinkPolicy::Right)
+    }
+
+    fn round(self) -> Self {
+        self.round_with(KinkPolicy::Right)
+    }
+
+    fn trunc(self) -> Self {
+        let v = Float::trunc(self.val());
+        self.chain(v, F::zero())
+    }
+
+    fn fract(self) -> Self {
+        let v = Float::fract(self.val());
+        self.chain(v, F::one())
+    }
+
+    fn abs(self) -> Self {
+        self.abs()
+    }
+
+    fn signum(self) -> Self {
+        self.signum_with(KinkPolicy::Right)
+    }
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        self.mul_add(&a, &b)
+    }
+
+    fn recip(self) -> Self {
+        self.inv()
+    }
+
+    fn powi(self, n: i32) -> Self {
+        self.powi(n)
+    }
+
+    fn powf(self, n: Self) -> Self {
+        // powdual goes through ln(self), which is NaN for negative bases
+        if n.diffs().iter().all(|d| d.is_zero()) {
+            self.powf(n.val())
+        } else {
+            self.powdual(n)
+        }
+    }
+
+    fn sqrt(self) -> Self {
+        self.sqrt()
+    }
+
+    fn exp(self) -> Self {
+        self.exp()
+    }
+
+    fn exp2(self) -> Self {
+        self.exp2()
+    }
+
+    fn ln(self) -> Self {
+        self.ln()
+    }
+
+    fn log(self, base: Self) -> Self {
+        self.ln() / base.ln()
+    }
+
+    fn log2(self) -> Self {
+        self.log2()
+    }
+
+    fn log10(self) -> Self {
+        self.log10()
+    }
+
+    fn to_degrees(self) -> Self {
+        self * (<F as NumCast>::from(180.).unwrap() / F::PI())
+    }
+
+    fn to_radians(self) -> Self {
+        self * (F::PI() / <F as NumCast>::from(180.).unwrap())
+    }
+
+    fn max(self, other: Self) -> Self {
+        if Float::is_nan(other.val()) {
+            self
+        } else if Float::is_nan(self.val()) {
+            other
+        } else {
+            self.max_with(other, KinkPolicy::Right)
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if Float::is_nan(other.val()) {
+            self
+        } else if Float::is_nan(self.val()) {
+            other
+        } else {
+            self.min_with(other, KinkPolicy::Right)
+        }
+    }
+
+    fn abs_sub(self, other: Self) -> Self {
+        Signed::abs_sub(&self, &other)
+    }
+
+    fn cbrt(self) -> Self {
+        self.cbrt()
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        self.hypot(other)
+    }
+
+    fn sin(self) -> Self {
+        self.sin()
+    }
+
+    fn cos(self) -> Self {
+        self.cos()
+    }
+
+    fn tan(self) -> Self {
+        self.tan()
+    }
+
+    fn asin(self) -> Self {
+        self.asin()
+    }
+
+    fn acos(self) -> Self {
+        self.acos()
+    }
+
+    fn atan(self) -> Self {
+        self.atan()
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        self.atan2(other)
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        self.sin_cos()
+    }
+
+    fn exp_m1(self) -> Self {
+        self.exp_m1()
+    }
+
+    fn ln_1p(self) -> Self {
+        self.ln_1p()
+    }
+
+    fn sinh(self) -> Self {
+        self.sinh()
+    }
+
+    fn cosh(self) -> Self {
+        self.cosh()
+    }
+
+    fn tanh(self) -> Self {
+        self.tanh()
+    }
+
+    fn asinh(self) -> Self {
+        self.asinh()
+    }
+
+    fn acosh(self) -> Self {
+        self.acosh()
+    }
+
+    fn atanh(self) -> Self {
+        self.atanh()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Dual, RW};
+    use num_traits::real::Real;
+    use num_traits::{Float, FloatConst, FromPrimitive, Num, One, Signed, ToPrimitive, Zero};
+
+    type Arr = Dual<[f64; 3], RW, f64>;
+
+    // Generic code, as found in user crates.
+    fn energy<T: Float>(x: T, v: T) -> T {
+        let half = T::from(0.5).unwrap();
+        half * v * v + (T::one() - x.cos()).max(T::zero()) + x.powi(2).sqrt().tanh()
+    }
+
+    fn norm<T: Real>(xs: &[T]) -> T {
+        xs.iter().fold(T::zero(), |acc, &x| acc.hypot(x))
+    }
+
+    #[test]
+    fn test_constants() {
+        let x = Arr::from([1.5, 1., -2.]);
+        assert_eq!(Arr::zero().as_slice(), &[0., 0., 0.]);
+        assert_eq!(x + Arr::zero(), x);
+        assert_eq!(Arr::zero() + x, x);
+        assert_eq!(x * Arr::one(), x);
+        assert_eq!(Arr::one() - x, 1. - x);
+        assert_eq!(Arr::one() / x, x.inv());
+        assert_eq!([x, x].iter().fold(Arr::zero(), |acc, &x| acc + x), x * 2.);
+        assert_eq!(Arr::zero(), Arr::constant(0., 2));
+        assert_eq!(Arr::PI(), Arr::constant(std::f64::consts::PI, 2));
+        assert_eq!(
+            <Arr as num_traits::NumCast>::from(3_u8),
+            Some(Arr::constant(3., 2))
+        );
+        assert_eq!(Arr::from_i32(-2), Some(Arr::constant(-2., 2)));
+        assert_eq!(
+            Arr::from_str_radix("2.5", 10).ok(),
+            Some(Arr::constant(2.5, 2))
+        );
+        assert!(Arr::zero().is_zero());
+        assert!(!Arr::from([0., 1., 0.]).is_zero());
+        assert_eq!(x.to_i32(), Some(1));
+    }
+
+    #[test]
+    fn test_rem_signed() {
+        let x = Arr::from([7.5, 1., 0.]);
+        let y = Arr::from([-2., 0., 1.]);
+        assert_eq!((x % y).as_slice(), &[1.5, 1., 3.]);
+        assert_eq!(x % Arr::constant(2., 2), x % 2.);
+        assert_eq!(Signed::abs(&y), -y);
+        assert_eq!(Signed::abs_sub(&y, &x).as_slice(), &[0., 0., 0.]);
+        assert_eq!(Signed::abs_sub(&x, &y), x - y);
+        assert_eq!(Signed::signum(&y).as_slice(), &[-1., 0., 0.]);
+        assert!(y.is_negative());
+    }
+
+    #[test]
+    fn test_float() {
+        let x = Arr::from([0.7, 1., 0.]);
+        let v = Arr::from([-1.2, 0., 1.]);
+        let res = energy(x, v);
+        let expected = v * v.view() * 0.5 + (1. - x.cos()) + x.powi(2).sqrt().tanh();
+        assert!(res.is_close(&expected, 1e-15));
+        assert!(Float::is_nan(Arr::nan()));
+        assert!(Float::max(Arr::nan(), x) == x);
+        let tie = Arr::from([0.7, 0., 1.]);
+        assert_eq!(Float::max(x, tie), x);
+        assert_eq!(Float::min(tie, x), x);
+        assert_eq!(Float::min(x, v), v);
+        assert_eq!(
+            Float::powf(x, Arr::constant(2., 2)),
+            x.powdual(Arr::constant(2., 2))
+        );
+        let neg = Arr::from([-2., 1., 0.]);
+        assert_eq!(
+            Float::powf(neg, Arr::constant(2., 2)).as_slice(),
+            &[4., -4., 0.]
+        );
+        assert!(Float::log(x, Arr::constant(2., 2)).is_close(&x.log2(), 1e-15));
+        assert_eq!(Float::trunc(v).as_slice(), &[-1., 0., 0.]);
+        assert_eq!(Float::fract(v), v + 1.);
+        assert!(x < v.abs());
+        let n = norm(&[x, v]);
+        assert!(n.is_close(&(x * x.view() + v * v.view()).sqrt(), 1e-15));
+    }
+}
@@ -10,18 +10,6 @@ use std::ops;
 //
 //
 
-macro_rules! check_same_ndiffs {
-    ($x : ident , $y : ident) => {
-        assert_eq!(
-            $x.ndiffs(),
-            $y.ndiffs(),
-            "Duals have different numbers of diffs: {} =/= {}.",
-            $x.ndiffs(),
-            $y.ndiffs()
-        );
-    };
-}
-
 // Derive multiple implementations of the ops from the XAssign<&Dual<_,_>> for Dual<_,RW> one
 macro_rules! derive_ops {
     ($opsname : ident, $opsassignname : ident, $fn_name:ident, $fnassign_name : ident) => {
@@ -325,12 +313,35 @@ where
 
 derive_ops!(Sub, SubAssign, sub, sub_assign);
 
+impl<L, R, M, F> ops::RemAssign<&Dual<R, M, F>> for Dual<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn rem_assign(&mut self, rhs: &Dual<R, M, F>) {
+        check_same_ndiffs!(self, rhs);
+        let vs = self.val();
+        let vr = rhs.val();
+        let q = (vs / vr).trunc();
+        *self.val_mut() = vs % vr;
+        self.diffs_mut()
+            .iter_mut()
+            .zip(rhs.diffs())
+            .for_each(|(ds, dr)| *ds -= q * *dr);
+    }
+}
+
+derive_ops!(Rem, RemAssign, rem, rem_assign);
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::instanciations::vecf64::Owning;
 
-    fn generate_pair() -> (Dual<Vec<f64>, RW, f64>, Dual<Vec<f64>, RW, f64>) {
+    fn generate_pair() -> (Owning, Owning) {
         let mut y = Owning::constant(42., 3);
         let mut x = Owning::constant(42., 3);
         x.diffs_mut()[0] = 17.;
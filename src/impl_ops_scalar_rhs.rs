@@ -146,6 +146,17 @@ where
     }
 }
 derive_ops!(Sub, SubAssign, sub, sub_assign);
+
+impl<S, F> ops::RemAssign<F> for Dual<S, RW, F>
+where
+    S: RWAble<F>,
+    F: Scalar,
+{
+    fn rem_assign(&mut self, rhs: F) {
+        *self.val_mut() %= rhs;
+    }
+}
+derive_ops!(Rem, RemAssign, rem, rem_assign);
 impl<S> ops::Sub<Dual<S, RW, f64>> for f64
 where
     S: RWAble<f64>,
@@ -4,9 +4,26 @@
     doc(include = "../Readme.md")
 )]
 
+use core::cmp::Ordering;
 use core::marker::PhantomData;
 use std::ops;
 
+// Panics if two duals do not have the same number of derivatives
+macro_rules! check_same_ndiffs {
+    ($x : ident , $y : ident) => {
+        assert_eq!(
+            $x.ndiffs(),
+            $y.ndiffs(),
+            "Duals have different numbers of diffs: {} =/= {}.",
+            $x.ndiffs(),
+            $y.ndiffs()
+        );
+    };
+}
+
+#[macro_use]
+mod macros;
+
 pub mod traits;
 use traits::Scalar;
 use traits::{ROAble, RWAble, ToOwning, ToView};
@@ -14,6 +31,26 @@ use traits::{ROAble, RWAble, ToOwning, ToView};
 pub mod owning_markers;
 pub use owning_markers::{CompatibleWith, OwningMode, RO, RW};
 
+pub mod nonsmooth;
+pub use nonsmooth::KinkPolicy;
+
+pub mod hyperdual;
+pub use hyperdual::HyperDual;
+
+pub mod taylor;
+pub use taylor::Taylor;
+
+pub mod sparse_dual;
+pub use sparse_dual::SparseDual;
+
+pub mod pattern;
+pub use pattern::Pattern;
+
+pub mod drivers;
+
+#[cfg(feature = "smallvec")]
+pub use smallvec;
+
 /// The struct implementing dual numbers.
 ///
 /// It is parametrized by a type <T> which stands for either a borrowed or an owned container,
@@ -24,8 +61,53 @@ pub use owning_markers::{CompatibleWith, OwningMode, RO, RW};
 /// To create a Dual based on a container `c`, use `Dual::from(c)`.
 /// See crate-level documentation for more information on how the container values are interpreted.
 /// ## Create a constant (derivatives equal to zero) dual
-/// A `constant` method is provided for RW `Dual`s backed by a `Vec` or an array (up to size 32).
-/// To prevent cluttering, the array implementations documentation has been hidden.
+/// A `constant` method is provided for RW `Dual`s backed by a `Vec`, an array of any size,
+/// or a `SmallVec` with the `smallvec` feature.
+///
+/// # Comparing Duals
+/// `==` compares the whole content of duals, value and derivatives, whereas `<`, `>`, `<=` and `>=`
+/// only compare their values, as generic code branching on them expects.
+/// Duals with the same value but different derivatives are thus neither smaller nor greater than each other,
+/// but not equal either.
+///
+/// # Using Duals in generic numeric code
+/// RW `Dual`s backed by an array implement the `num_traits` traits
+/// `Zero`, `One`, `Num`, `NumCast`, `ToPrimitive`, `FromPrimitive`, `Signed`, `FloatConst`,
+/// and, being `Copy`, `Float`, and hence `Real`.
+///
+/// Constants built by these traits, such as `Zero::zero()`, have as many null derivatives as the array holds.
+/// Duals backed by a `Vec` or a `SmallVec` do not implement them: their number of derivatives is only known at
+/// runtime, and binary operations require both operands to have the same number of derivatives.
+///
+/// ```
+/// # use fwd_ad::*;
+/// use num_traits::Float;
+///
+/// fn f<T: Float>(x: T) -> T {
+///     (x * x + T::one()).sqrt().max(T::from(2.).unwrap())
+/// }
+///
+/// let x = Dual::<_, RW, f64>::from([3., 1.]);
+/// let expected = Dual::<_, RW, f64>::from([10_f64.sqrt(), 3. / 10_f64.sqrt()]);
+/// assert!(f(x).is_close(&expected, 1e-15));
+/// ```
+///
+/// # Nesting Duals
+/// RW `Dual`s backed by an array of `f32` or `f64` are themselves `Scalar`s,
+/// so that they can be used as the scalar type of other duals, backed by any container, to get higher derivatives.
+/// Duals backed by a `Vec` or a `SmallVec` are not `Scalar`s, not being `Copy`: they can only be the outermost level.
+/// Each nesting level is a distinct type: a dual of the outer level can only see the inner ones as its scalars,
+/// and conversely, so that their perturbations cannot be confused.
+///
+/// ```
+/// # use fwd_ad::*;
+/// type D = Dual<[f64; 2], RW, f64>;
+/// // x = 2, with both its inner and outer derivatives set to 1.
+/// let x = Dual::<[D; 2], RW, D>::from([D::from([2., 1.]), D::from([1., 0.])]);
+/// let res = x.powi(3);
+/// assert_eq!(res.val(), D::from([8., 12.]));
+/// assert_eq!(res.diffs()[0], D::from([12., 12.]));
+/// ```
 #[derive(PartialEq, Debug, Clone, Copy, Hash, Default)]
 pub struct Dual<T, M, F>
 where
@@ -53,6 +135,18 @@ where
     }
 }
 
+/// Duals are ordered by their value only, so that branches in generic code follow the value.
+impl<T, M, F> PartialOrd for Dual<T, M, F>
+where
+    M: OwningMode + PartialEq,
+    T: ROAble<F> + CompatibleWith<M, F> + PartialEq,
+    F: Scalar,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.val().partial_cmp(&other.val())
+    }
+}
+
 impl<F> Dual<Vec<F>, RW, F>
 where
     F: Scalar,
@@ -66,56 +160,37 @@ where
     }
 }
 
-mod array_constant_impl {
-    use super::{Dual, Scalar, RW};
-    macro_rules! impl_array {
-        ($n:literal) => {
-            #[doc(hidden)]
-            impl<F> Dual<[F; $n], RW, F>
-            where
-                F: Scalar,
-            {
-                pub fn constant(value: F, ndiffs: usize) -> Self {
-                    assert_eq!(ndiffs + 1, $n);
-                    let mut res = Dual::from([F::zero(); $n]);
-                    res.content[0] = value;
-                    res
-                }
-            }
-        };
-    }
-    impl_array!(1);
-    impl_array!(2);
-    impl_array!(3);
-    impl_array!(4);
-    impl_array!(5);
-    impl_array!(6);
-    impl_array!(7);
-    impl_array!(8);
-    impl_array!(9);
-    impl_array!(10);
-    impl_array!(11);
-    impl_array!(12);
-    impl_array!(13);
-    impl_array!(14);
-    impl_array!(15);
-    impl_array!(16);
-    impl_array!(17);
-    impl_array!(18);
-    impl_array!(19);
-    impl_array!(20);
-    impl_array!(21);
-    impl_array!(22);
-    impl_array!(23);
-    impl_array!(24);
-    impl_array!(25);
-    impl_array!(26);
-    impl_array!(27);
-    impl_array!(28);
-    impl_array!(29);
-    impl_array!(30);
-    impl_array!(31);
-    impl_array!(32);
+#[cfg(feature = "smallvec")]
+impl<A, F> Dual<smallvec::SmallVec<A>, RW, F>
+where
+    A: smallvec::Array<Item = F>,
+    F: Scalar,
+{
+    /// Generates a dual number backed by a `SmallVec` with value `value` and `ndiffs`
+    /// differentials, set to 0. It spills on the heap if `ndiffs + 1` exceeds its inline capacity.
+    pub fn constant(value: F, ndiffs: usize) -> Self {
+        let mut res = Dual::from(smallvec::SmallVec::from_elem(F::zero(), ndiffs + 1));
+        res.content[0] = value;
+        res
+    }
+}
+
+impl<F, const N: usize> Dual<[F; N], RW, F>
+where
+    F: Scalar,
+{
+    /// Generates a dual number backed by an array with value `value` and `ndiffs`
+    /// differentials, set to 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ndiffs + 1` is not the size of the array.
+    pub fn constant(value: F, ndiffs: usize) -> Self {
+        assert_eq!(ndiffs + 1, N);
+        let mut res = Dual::from([F::zero(); N]);
+        res.content[0] = value;
+        res
+    }
 }
 
 /// Implementations for Duals that do not necessarily own their content.
@@ -339,22 +414,61 @@ where
         S: ROAble<F>,
         S: CompatibleWith<M2, F>,
     {
-        let vs = self.val();
-        if vs == F::zero() {
-            for ds in self.diffs_mut() {
-                *ds = F::zero()
-            }
-            return self;
-        }
-        let ve = exp.val();
-        *self.val_mut() = vs.powf(ve);
+        let (val, dbase, dexp) = rules::powdual(self.val(), exp.val());
+        *self.val_mut() = val;
         self.diffs_mut()
             .iter_mut()
             .zip(exp.diffs())
-            .for_each(|(ds, de)| *ds = vs.powf(ve - F::one()) * (vs * de * vs.ln() + ve * *ds));
+            .for_each(|(ds, de)| *ds = dbase * *ds + dexp * *de);
         self
     }
 
+    /// Returns self * a + b, computed in a single pass over the derivatives.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let x = Dual::<_, RW, f64>::from([2., 1., 0.]);
+    /// let a = Dual::<_, RW, f64>::from([3., 0., 1.]);
+    /// let b = Dual::<_, RW, f64>::from([1., 1., 1.]);
+    /// assert_eq!(x.clone().mul_add(&a, &b), x * &a + &b);
+    /// ```
+    pub fn mul_add<S1, M1, S2, M2>(mut self, a: &Dual<S1, M1, F>, b: &Dual<S2, M2, F>) -> Self
+    where
+        M1: OwningMode,
+        S1: ROAble<F>,
+        S1: CompatibleWith<M1, F>,
+        M2: OwningMode,
+        S2: ROAble<F>,
+        S2: CompatibleWith<M2, F>,
+    {
+        assert!(
+            self.ndiffs() == a.ndiffs() && self.ndiffs() == b.ndiffs(),
+            "Duals have different numbers of diffs: {}, {}, {}.",
+            self.ndiffs(),
+            a.ndiffs(),
+            b.ndiffs()
+        );
+        let vs = self.val();
+        let va = a.val();
+        *self.val_mut() = vs.mul_add(va, b.val());
+        self.diffs_mut()
+            .iter_mut()
+            .zip(a.diffs())
+            .zip(b.diffs())
+            .for_each(|((ds, da), db)| *ds = vs * *da + va * *ds + *db);
+        self
+    }
+
+    /// Returns self * a + b, with a and b scalars, computed in a single pass over the derivatives.
+    pub fn mul_add_scalar(mut self, a: F, b: F) -> Self {
+        *self.val_mut() = self.val().mul_add(a, b);
+        self.diffs_mut().iter_mut().for_each(|ds| *ds *= a);
+        self
+    }
+
+    /// Returns |self|.
+    ///
+    /// At 0, the derivative of the positive branch is used, as with `abs_with(KinkPolicy::Right)`.
     pub fn abs(self) -> Self {
         let v = self.val();
         if v < F::zero() {
@@ -363,6 +477,266 @@ where
             self
         }
     }
+
+    /// Sets the value to `val` and multiplies the differentials by `dval`,
+    /// the derivative of the function applied to self.
+    ///
+    /// If `dval` is infinite, null differentials are kept null instead of becoming NaN.
+    fn chain(mut self, val: F, dval: F) -> Self {
+        *self.val_mut() = val;
+        rules::scale(self.diffs_mut(), dval);
+        self
+    }
+
+    /// Returns sin(self).
+    pub fn sin(self) -> Self {
+        let (val, dval) = rules::sin(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns cos(self).
+    pub fn cos(self) -> Self {
+        let (val, dval) = rules::cos(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns tan(self).
+    pub fn tan(self) -> Self {
+        let (val, dval) = rules::tan(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns (sin(self), cos(self)).
+    ///
+    /// The sine is computed in place, while the cosine is stored in a clone of self.
+    /// Both are filled during the same pass over the differentials.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let x = Dual::<_, RW, f64>::from(vec![0., 1., 2.]);
+    /// let (s, c) = x.sin_cos();
+    /// assert_eq!(s, Dual::<_, RW, f64>::from(vec![0., 1., 2.]));
+    /// assert_eq!(c, Dual::<_, RW, f64>::from(vec![1., 0., 0.]));
+    /// ```
+    pub fn sin_cos(mut self) -> (Self, Dual<T::Owning, RW, F>)
+    where
+        T: ToOwning<F>,
+    {
+        let (s, c) = self.val().sin_cos();
+        let mut cos = self.to_owning();
+        *self.val_mut() = s;
+        *cos.val_mut() = c;
+        for (ds, dc) in self.diffs_mut().iter_mut().zip(cos.diffs_mut()) {
+            *dc *= -s;
+            *ds *= c;
+        }
+        (self, cos)
+    }
+
+    /// Returns asin(self).
+    ///
+    /// At the edges of the domain, ±1, the derivative is unbounded:
+    /// non-null differentials become +∞ or -∞ (following their sign),
+    /// while null differentials stay null.
+    /// Outside of [-1, 1], the value and differentials are NaN.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let x = Dual::<_, RW, f64>::from([1., 1., 0., -2.]).asin();
+    /// assert_eq!(x.val(), std::f64::consts::FRAC_PI_2);
+    /// assert_eq!(x.diffs(), &[f64::INFINITY, 0., f64::NEG_INFINITY]);
+    /// ```
+    pub fn asin(self) -> Self {
+        let (val, dval) = rules::asin(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns acos(self).
+    ///
+    /// At the edges of the domain, ±1, the derivative is unbounded:
+    /// non-null differentials become -∞ or +∞ (following the opposite of their sign),
+    /// while null differentials stay null.
+    /// Outside of [-1, 1], the value and differentials are NaN.
+    pub fn acos(self) -> Self {
+        let (val, dval) = rules::acos(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns atan(self).
+    pub fn atan(self) -> Self {
+        let (val, dval) = rules::atan(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns the four quadrant arctangent of self (y) and other (x), in radians.
+    ///
+    /// At the origin, where atan2 is not differentiable, the differentials are set to 0.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let y = Dual::<_, RW, f64>::from([1., 1., 0.]);
+    /// let x = Dual::<_, RW, f64>::from([1., 0., 1.]);
+    /// let res = y.atan2(x);
+    /// assert_eq!(res.val(), std::f64::consts::FRAC_PI_4);
+    /// assert_eq!(res.diffs(), &[0.5, -0.5]);
+    /// ```
+    pub fn atan2<S, M2>(mut self, other: Dual<S, M2, F>) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        check_same_ndiffs!(self, other);
+        let (val, wself, wother) = rules::atan2(self.val(), other.val());
+        *self.val_mut() = val;
+        self.diffs_mut()
+            .iter_mut()
+            .zip(other.diffs())
+            .for_each(|(ds, dof)| *ds = wself * *ds + wother * *dof);
+        self
+    }
+
+    /// Returns sinh(self).
+    pub fn sinh(self) -> Self {
+        let (val, dval) = rules::sinh(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns cosh(self).
+    pub fn cosh(self) -> Self {
+        let (val, dval) = rules::cosh(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns tanh(self).
+    pub fn tanh(self) -> Self {
+        let (val, dval) = rules::tanh(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns asinh(self).
+    pub fn asinh(self) -> Self {
+        let (val, dval) = rules::asinh(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns acosh(self).
+    ///
+    /// At the edge of the domain, 1, the derivative is unbounded:
+    /// non-null differentials become +∞ or -∞ (following their sign),
+    /// while null differentials stay null.
+    /// Below 1, the value and differentials are NaN.
+    pub fn acosh(self) -> Self {
+        let (val, dval) = rules::acosh(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns atanh(self).
+    ///
+    /// At the edges of the domain, ±1, the derivative is unbounded:
+    /// non-null differentials become +∞ or -∞ (following their sign),
+    /// while null differentials stay null.
+    /// Outside of [-1, 1], the value and differentials are NaN.
+    pub fn atanh(self) -> Self {
+        let (val, dval) = rules::atanh(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns the square root of self.
+    ///
+    /// At 0, the derivative is unbounded: non-null differentials become
+    /// +∞ or -∞ (following their sign), while null differentials stay null.
+    pub fn sqrt(self) -> Self {
+        let (val, dval) = rules::sqrt(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns the cube root of self.
+    ///
+    /// At 0, the derivative is unbounded: non-null differentials become
+    /// +∞ or -∞ (following their sign), while null differentials stay null.
+    pub fn cbrt(self) -> Self {
+        let (val, dval) = rules::cbrt(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns 1/sqrt(self).
+    pub fn recip_sqrt(self) -> Self {
+        let (val, dval) = rules::recip_sqrt(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns self^n.
+    ///
+    /// The derivative is exact, including at 0 for positive exponents.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let x = Dual::<_, RW, f64>::from([0., 1.]);
+    /// assert_eq!(x.powi(1).diffs(), &[1.]);
+    /// assert_eq!(x.powi(2).diffs(), &[0.]);
+    /// assert_eq!(x.powi(0).diffs(), &[0.]);
+    /// ```
+    pub fn powi(self, n: i32) -> Self {
+        let (val, dval) = rules::powi(self.val(), n);
+        self.chain(val, dval)
+    }
+
+    /// Returns sqrt(self^2 + other^2).
+    ///
+    /// At the origin, where hypot is not differentiable, the differentials are set to 0.
+    pub fn hypot<S, M2>(mut self, other: Dual<S, M2, F>) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        check_same_ndiffs!(self, other);
+        let (val, wself, wother) = rules::hypot(self.val(), other.val());
+        *self.val_mut() = val;
+        self.diffs_mut()
+            .iter_mut()
+            .zip(other.diffs())
+            .for_each(|(ds, dof)| *ds = wself * *ds + wother * *dof);
+        self
+    }
+
+    /// Returns log2(self).
+    pub fn log2(self) -> Self {
+        let (val, dval) = rules::log2(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns log10(self).
+    pub fn log10(self) -> Self {
+        let (val, dval) = rules::log10(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns the logarithm of self with respect to an arbitrary base.
+    pub fn log(self, base: F) -> Self {
+        let (val, dval) = rules::log(self.val(), base);
+        self.chain(val, dval)
+    }
+
+    /// Returns ln(1+self), more accurately than if the operations were performed separately.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let x = Dual::<_, RW, f64>::from([1e-20, 1.]).ln_1p();
+    /// assert_eq!(x.val(), 1e-20);
+    /// assert_eq!(x.diffs(), &[1.]);
+    /// ```
+    pub fn ln_1p(self) -> Self {
+        let (val, dval) = rules::ln_1p(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns e^self - 1, more accurately than if the operations were performed separately.
+    pub fn exp_m1(self) -> Self {
+        let (val, dval) = rules::exp_m1(self.val());
+        self.chain(val, dval)
+    }
 }
 
 impl<T, F> ops::Neg for Dual<T, RW, F>
@@ -405,6 +779,45 @@ mod implicit_clone {
         clone_impl!(inv());
         clone_impl!(powf(exp: F));
         clone_impl!(abs());
+        clone_impl!(sin());
+        clone_impl!(cos());
+        clone_impl!(tan());
+        clone_impl!(asin());
+        clone_impl!(acos());
+        clone_impl!(atan());
+        clone_impl!(sinh());
+        clone_impl!(cosh());
+        clone_impl!(tanh());
+        clone_impl!(asinh());
+        clone_impl!(acosh());
+        clone_impl!(atanh());
+        clone_impl!(sqrt());
+        clone_impl!(cbrt());
+        clone_impl!(recip_sqrt());
+        clone_impl!(powi(n: i32));
+        clone_impl!(log2());
+        clone_impl!(log10());
+        clone_impl!(log(base: F));
+        clone_impl!(ln_1p());
+        clone_impl!(exp_m1());
+        clone_impl!(erf());
+        clone_impl!(erfc());
+        clone_impl!(normal_cdf());
+        clone_impl!(probit());
+        clone_impl!(gamma());
+        clone_impl!(ln_gamma());
+        clone_impl!(digamma());
+        clone_impl!(bessel_j(n: i32));
+        clone_impl!(bessel_y(n: i32));
+        clone_impl!(bessel_i(nu: F));
+        clone_impl!(bessel_k(nu: F));
+        clone_impl!(mul_add_scalar(a: F, b: F));
+        clone_impl!(abs_with(policy: KinkPolicy));
+        clone_impl!(clamp_with(lo: F, hi: F, policy: KinkPolicy));
+        clone_impl!(signum_with(policy: KinkPolicy));
+        clone_impl!(floor_with(policy: KinkPolicy));
+        clone_impl!(ceil_with(policy: KinkPolicy));
+        clone_impl!(round_with(policy: KinkPolicy));
 
         pub fn powdual<S, M2>(self, exp: Dual<S, M2, F>) -> Dual<T::Owning, RW, F>
         where
@@ -415,6 +828,142 @@ mod implicit_clone {
             let res = self.to_owning();
             res.powdual(exp)
         }
+
+        pub fn mul_add<S1, M1, S2, M2>(
+            self,
+            a: &Dual<S1, M1, F>,
+            b: &Dual<S2, M2, F>,
+        ) -> Dual<T::Owning, RW, F>
+        where
+            M1: OwningMode,
+            S1: ROAble<F>,
+            S1: CompatibleWith<M1, F>,
+            M2: OwningMode,
+            S2: ROAble<F>,
+            S2: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.mul_add(a, b)
+        }
+
+        pub fn atan2<S, M2>(self, other: Dual<S, M2, F>) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.atan2(other)
+        }
+
+        pub fn hypot<S, M2>(self, other: Dual<S, M2, F>) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.hypot(other)
+        }
+
+        pub fn gamma_p<S, M2>(self, a: Dual<S, M2, F>) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.gamma_p(a)
+        }
+
+        pub fn gamma_q<S, M2>(self, a: Dual<S, M2, F>) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.gamma_q(a)
+        }
+
+        pub fn beta_inc<S1, M1, S2, M2>(
+            self,
+            a: Dual<S1, M1, F>,
+            b: Dual<S2, M2, F>,
+        ) -> Dual<T::Owning, RW, F>
+        where
+            M1: OwningMode,
+            S1: ROAble<F>,
+            S1: CompatibleWith<M1, F>,
+            M2: OwningMode,
+            S2: ROAble<F>,
+            S2: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.beta_inc(a, b)
+        }
+
+        pub fn bessel_i_dual<S, M2>(self, nu: Dual<S, M2, F>) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.bessel_i_dual(nu)
+        }
+
+        pub fn bessel_k_dual<S, M2>(self, nu: Dual<S, M2, F>) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.bessel_k_dual(nu)
+        }
+
+        pub fn min_with<S, M2>(
+            self,
+            other: Dual<S, M2, F>,
+            policy: KinkPolicy,
+        ) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.min_with(other, policy)
+        }
+
+        pub fn max_with<S, M2>(
+            self,
+            other: Dual<S, M2, F>,
+            policy: KinkPolicy,
+        ) -> Dual<T::Owning, RW, F>
+        where
+            M2: OwningMode,
+            S: ROAble<F>,
+            S: CompatibleWith<M2, F>,
+        {
+            let res = self.to_owning();
+            res.max_with(other, policy)
+        }
+
+        #[allow(clippy::type_complexity)]
+        pub fn sin_cos(&self) -> (Dual<T::Owning, RW, F>, Dual<T::Owning, RW, F>) {
+            let (s, c) = self.val().sin_cos();
+            let mut sin = self.to_owning();
+            let mut cos = self.to_owning();
+            *sin.val_mut() = s;
+            *cos.val_mut() = c;
+            for (ds, dc) in sin.diffs_mut().iter_mut().zip(cos.diffs_mut()) {
+                *dc *= -s;
+                *ds *= c;
+            }
+            (sin, cos)
+        }
     }
 
     impl<T, F> ops::Neg for Dual<T, RO, F>
@@ -431,8 +980,11 @@ mod implicit_clone {
 }
 
 mod generate_duals;
+mod impl_num_traits;
 mod impl_ops_dual;
 mod impl_ops_scalar_rhs;
+mod rules;
+mod special;
 
 pub mod instanciations;
 
@@ -460,6 +1012,17 @@ mod tests {
         assert_eq!(x.ndiffs(), 42);
     }
 
+    #[test]
+    fn test_ordering() {
+        let x = Owning::from(vec![1., 1.]);
+        let y = Owning::from(vec![1., -5.]);
+        assert_eq!((y < x, x < y, x == y), (false, false, false));
+        assert_eq!((x <= y, x >= y), (true, true));
+        assert_eq!(x.partial_cmp(&y), Some(Ordering::Equal));
+        assert_eq!(x.partial_cmp(&x.clone()), Some(Ordering::Equal));
+        assert!(Owning::from(vec![0.5, 7.]) < y);
+    }
+
     #[test]
     fn test_neg() {
         let x = generate();
@@ -472,4 +1035,178 @@ mod tests {
         assert!(x.clone().is_close(&x.clone().exp().ln(), 1e-8));
         assert!(x.clone().is_close(&x.clone().ln().exp(), 1e-8));
     }
+
+    #[test]
+    fn test_logs() {
+        let x = generate();
+        assert!(x
+            .clone()
+            .log2()
+            .is_close(&(x.clone().ln() / 2_f64.ln()), 1e-8));
+        assert!(x
+            .clone()
+            .log10()
+            .is_close(&(x.clone().ln() / 10_f64.ln()), 1e-8));
+        assert!(x
+            .clone()
+            .log(3.)
+            .is_close(&(x.clone().ln() / 3_f64.ln()), 1e-8));
+        assert!(x.clone().log(3.).exp_base(3.).is_close(&x, 1e-8));
+        let y = generate() / 1000.;
+        assert!(y.clone().ln_1p().is_close(&(y.clone() + 1.).ln(), 1e-8));
+        assert!(y.clone().exp_m1().is_close(&(y.clone().exp() - 1.), 1e-8));
+        assert!(y.clone().exp_m1().ln_1p().is_close(&y, 1e-12));
+    }
+
+    #[test]
+    fn test_trigo() {
+        let x = generate();
+        let (s, c) = x.clone().sin_cos();
+        assert_eq!(s, x.clone().sin());
+        assert_eq!(c, x.clone().cos());
+        let one = s.clone() * s.view() + c.clone() * c.view();
+        assert!(one.is_close(&Owning::constant(1., 3), 1e-8));
+        assert!(x.clone().tan().is_close(&(s / c), 1e-8));
+    }
+
+    #[test]
+    fn test_inverse_trigo() {
+        let x = generate() / 100.;
+        assert!(x.clone().sin().asin().is_close(&x, 1e-8));
+        assert!(x.clone().cos().acos().is_close(&x, 1e-8));
+        assert!(x.clone().tan().atan().is_close(&x, 1e-8));
+        let y = Owning::from(vec![0.5, 1., 0., -1.]);
+        let res = (y.clone() / x.view()).atan();
+        assert!(y.atan2(x).is_close(&res, 1e-8));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_atan2_different_ndiffs() {
+        let _ = generate().atan2(Owning::constant(1., 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hypot_different_ndiffs() {
+        let _ = generate().hypot(Owning::constant(1., 2));
+    }
+
+    #[test]
+    fn test_hyperbolic() {
+        let x = generate() / 100.;
+        let sinh = (x.clone().exp() - (-x.clone()).exp()) / 2.;
+        let cosh = (x.clone().exp() + (-x.clone()).exp()) / 2.;
+        assert!(x.clone().sinh().is_close(&sinh, 1e-8));
+        assert!(x.clone().cosh().is_close(&cosh, 1e-8));
+        assert!(x.clone().tanh().is_close(&(sinh / cosh), 1e-8));
+        assert!(x.clone().sinh().asinh().is_close(&x, 1e-8));
+        assert!(x.clone().cosh().acosh().is_close(&x, 1e-8));
+        assert!(x.clone().tanh().atanh().is_close(&x, 1e-8));
+    }
+
+    #[test]
+    fn test_mul_add() {
+        let x = generate();
+        let a = Owning::from(vec![-1.5, 2., 0.5, -3.]);
+        let b = Owning::from(vec![0.25, 1., -1., 4.]);
+        let res = x.clone().mul_add(&a.view(), &b);
+        let expected = x.clone() * &a + &b;
+        for (r, e) in res.as_slice().iter().zip(expected.as_slice()) {
+            assert!((r - e).abs() < 1e-15);
+        }
+        assert_eq!(x.clone().mul_add_scalar(-2., 3.), x.clone() * -2. + 3.);
+    }
+
+    #[test]
+    fn test_roots_powers() {
+        let x = generate();
+        assert!(x.clone().sqrt().is_close(&x.clone().powf(0.5), 1e-8));
+        assert!(x.clone().cbrt().is_close(&x.clone().powf(1. / 3.), 1e-8));
+        assert!(x.clone().recip_sqrt().is_close(&x.clone().powf(-0.5), 1e-8));
+        assert!(x.clone().powi(3).is_close(&x.clone().powf(3.), 1e-6));
+        assert!(x.clone().powi(-2).is_close(&x.clone().powf(-2.), 1e-8));
+        let y = generate() * 0.5;
+        let h = (x.clone() * x.view() + y.clone() * y.view()).sqrt();
+        assert!(x.hypot(y).is_close(&h, 1e-8));
+    }
+
+    #[test]
+    fn test_nested() {
+        // Inner duals carry the first derivative, outer ones the derivative of the inner ones.
+        type D = Dual<[f64; 2], RW, f64>;
+        fn f<T: Scalar>(x: T) -> T {
+            x.powi(3) * x.sin() + x.exp() / (x + T::one())
+        }
+        let x0 = 0.7_f64;
+        let x = Dual::<[D; 2], RW, D>::from([D::from([x0, 1.]), D::from([1., 0.])]);
+        let res = f(x);
+        let (s, c, e) = (x0.sin(), x0.cos(), x0.exp());
+        let d1 = 3. * x0 * x0 * s + x0.powi(3) * c + e * x0 / (x0 + 1.).powi(2);
+        let d2 = 6. * x0 * s + 6. * x0 * x0 * c - x0.powi(3) * s
+            + e * (x0 * x0 + 1.) / (x0 + 1.).powi(3);
+        assert!(res.val().is_close(&D::from([f(x0), d1]), 1e-14));
+        assert!(res.diffs()[0].is_close(&D::from([d1, d2]), 1e-13));
+
+        // Vec backed outer duals, and nesting levels kept apart by their types:
+        // d/dx (x * d/dy (x + y)) = 1
+        let x = D::from([x0, 1.]);
+        let y = Dual::<Vec<D>, RW, D>::from(vec![D::from([2., 0.]), D::constant(1., 1)]);
+        let inner = (y + x).diffs()[0];
+        assert_eq!((x * inner).diffs(), &[1.]);
+    }
+
+    #[test]
+    fn test_nested_boundaries() {
+        // Guards at special points must only look at the values of nested duals.
+        type D = Dual<[f64; 2], RW, f64>;
+        type N = Dual<[D; 2], RW, D>;
+        let x = N::from([D::from([0., 1.]), D::from([1., 0.])]);
+        let a = N::from([D::constant(2., 1), D::constant(0., 1)]);
+        // P(2, x) ~ x²/2
+        let p = x.gamma_p(a);
+        assert_eq!(p.val(), D::constant(0., 1));
+        assert!(p.diffs()[0].is_close(&D::from([0., 1.]), 1e-12));
+        // I_1(x) ~ x/2
+        let i = x.bessel_i(D::constant(1., 1));
+        assert_eq!(i.as_slice(), &[D::from([0., 0.5]), D::from([0.5, 0.])]);
+        let y = N::from([D::from([1., 1.]), D::from([1., 0.])]);
+        let c = y.clamp_with(D::constant(0., 1), D::constant(1., 1), KinkPolicy::Left);
+        assert_eq!(c, y);
+        // Branches of generic code follow the values
+        fn relu<T: num_traits::Float>(x: T) -> T {
+            if x >= T::zero() {
+                x
+            } else {
+                T::zero()
+            }
+        }
+        assert_eq!(relu(D::from([0., 1.])).as_slice(), &[0., 1.]);
+    }
+
+    #[test]
+    fn test_perturbation_confusion() {
+        // d/dx (x * d/dy (x * y)) = d/dx x² = 2x, whereas sharing a single perturbation between x and y
+        // would give d/dx (x * (x + y)) = 2x + y.
+        type D = Dual<[f64; 2], RW, f64>;
+        let x = D::from([3., 1.]);
+        let y = Dual::<[D; 2], RW, D>::from([D::constant(2., 1), D::constant(1., 1)]);
+        let inner = (y * x).diffs()[0];
+        assert_eq!(inner, x);
+        assert_eq!((x * inner).as_slice(), &[9., 6.]);
+    }
+
+    #[test]
+    fn test_domain_edges() {
+        let x = Owning::from(vec![-1., 2., 0.]);
+        assert_eq!(x.clone().asin().diffs(), &[f64::INFINITY, 0.]);
+        assert_eq!(x.clone().acos().diffs(), &[f64::NEG_INFINITY, 0.]);
+        assert_eq!(x.atanh().diffs(), &[f64::INFINITY, 0.]);
+        let zero = Owning::from(vec![0., -1., 0.]);
+        assert_eq!(zero.clone().sqrt().diffs(), &[f64::NEG_INFINITY, 0.]);
+        assert_eq!(zero.clone().cbrt().diffs(), &[f64::NEG_INFINITY, 0.]);
+        assert_eq!(zero.clone().hypot(zero).diffs(), &[0., 0.]);
+        let origin = Owning::constant(0., 2);
+        assert_eq!(origin.clone().atan2(origin).diffs(), &[0., 0.]);
+    }
 }
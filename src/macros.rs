@@ -0,0 +1,187 @@
+//! Macros deriving the operators of the number types other than `Dual`.
+//!
+//! `HyperDual`s and `Taylor`s are backed by a container, as `Dual`s, while `SparseDual`s own their scalars:
+//! each macro has an arm for each of these two shapes, selected by the generic parameters of the type.
+
+// Derive multiple implementations of the ops from the XAssign<&_> and XAssign<F> ones
+macro_rules! derive_ops_for {
+    ($t : ident<T, M, F>: $opsname : ident, $opsassignname : ident, $fn_name:ident, $fnassign_name : ident) => {
+        impl<L, R, M, F> std::ops::$opsassignname<$t<R, M, F>> for $t<L, $crate::RW, F>
+        where
+            M: $crate::OwningMode,
+            L: $crate::traits::RWAble<F>,
+            R: $crate::traits::ROAble<F>,
+            R: $crate::CompatibleWith<M, F>,
+            F: $crate::traits::Scalar,
+        {
+            fn $fnassign_name(&mut self, rhs: $t<R, M, F>) {
+                std::ops::$opsassignname::$fnassign_name(self, &rhs)
+            }
+        }
+
+        impl<L, R, M, F> std::ops::$opsname<$t<R, M, F>> for $t<L, $crate::RW, F>
+        where
+            M: $crate::OwningMode,
+            L: $crate::traits::RWAble<F>,
+            R: $crate::traits::ROAble<F>,
+            R: $crate::CompatibleWith<M, F>,
+            F: $crate::traits::Scalar,
+        {
+            type Output = Self;
+            fn $fn_name(mut self, rhs: $t<R, M, F>) -> Self {
+                std::ops::$opsassignname::$fnassign_name(&mut self, &rhs);
+                self
+            }
+        }
+
+        impl<L, R, M, F> std::ops::$opsname<&$t<R, M, F>> for $t<L, $crate::RW, F>
+        where
+            M: $crate::OwningMode,
+            L: $crate::traits::RWAble<F>,
+            R: $crate::traits::ROAble<F>,
+            R: $crate::CompatibleWith<M, F>,
+            F: $crate::traits::Scalar,
+        {
+            type Output = Self;
+            fn $fn_name(mut self, rhs: &$t<R, M, F>) -> Self {
+                std::ops::$opsassignname::$fnassign_name(&mut self, rhs);
+                self
+            }
+        }
+
+        impl<L, F> std::ops::$opsname<F> for $t<L, $crate::RW, F>
+        where
+            L: $crate::traits::RWAble<F>,
+            F: $crate::traits::Scalar,
+        {
+            type Output = Self;
+            fn $fn_name(mut self, rhs: F) -> Self {
+                std::ops::$opsassignname::$fnassign_name(&mut self, rhs);
+                self
+            }
+        }
+
+        #[cfg(feature = "implicit-clone")]
+        impl<L, R, MR, F> std::ops::$opsname<&$t<R, MR, F>> for $t<L, $crate::RO, F>
+        where
+            L: $crate::traits::ToOwning<F>,
+            MR: $crate::OwningMode,
+            R: $crate::traits::ROAble<F>,
+            R: $crate::CompatibleWith<MR, F>,
+            F: $crate::traits::Scalar,
+        {
+            type Output = $t<L::Owning, $crate::RW, F>;
+            fn $fn_name(self, rhs: &$t<R, MR, F>) -> Self::Output {
+                let mut res = self.to_owning();
+                std::ops::$opsassignname::$fnassign_name(&mut res, rhs);
+                res
+            }
+        }
+
+        #[cfg(feature = "implicit-clone")]
+        impl<L, F> std::ops::$opsname<F> for $t<L, $crate::RO, F>
+        where
+            L: $crate::traits::ToOwning<F>,
+            F: $crate::traits::Scalar,
+        {
+            type Output = $t<L::Owning, $crate::RW, F>;
+            fn $fn_name(self, rhs: F) -> Self::Output {
+                let mut res = self.to_owning();
+                std::ops::$opsassignname::$fnassign_name(&mut res, rhs);
+                res
+            }
+        }
+    };
+    ($t : ident<F>: $opsname : ident, $opsassignname : ident, $fn_name:ident, $fnassign_name : ident) => {
+        impl<F: $crate::traits::Scalar> std::ops::$opsassignname<$t<F>> for $t<F> {
+            fn $fnassign_name(&mut self, rhs: $t<F>) {
+                std::ops::$opsassignname::$fnassign_name(self, &rhs)
+            }
+        }
+
+        impl<F: $crate::traits::Scalar> std::ops::$opsname<$t<F>> for $t<F> {
+            type Output = Self;
+            fn $fn_name(mut self, rhs: $t<F>) -> Self {
+                std::ops::$opsassignname::$fnassign_name(&mut self, &rhs);
+                self
+            }
+        }
+
+        impl<F: $crate::traits::Scalar> std::ops::$opsname<&$t<F>> for $t<F> {
+            type Output = Self;
+            fn $fn_name(mut self, rhs: &$t<F>) -> Self {
+                std::ops::$opsassignname::$fnassign_name(&mut self, rhs);
+                self
+            }
+        }
+
+        impl<F: $crate::traits::Scalar> std::ops::$opsname<F> for $t<F> {
+            type Output = Self;
+            fn $fn_name(mut self, rhs: F) -> Self {
+                std::ops::$opsassignname::$fnassign_name(&mut self, rhs);
+                self
+            }
+        }
+
+        #[cfg(feature = "implicit-clone")]
+        impl<F: $crate::traits::Scalar> std::ops::$opsname<&$t<F>> for &$t<F> {
+            type Output = $t<F>;
+            fn $fn_name(self, rhs: &$t<F>) -> $t<F> {
+                let mut res = self.clone();
+                std::ops::$opsassignname::$fnassign_name(&mut res, rhs);
+                res
+            }
+        }
+
+        #[cfg(feature = "implicit-clone")]
+        impl<F: $crate::traits::Scalar> std::ops::$opsname<F> for &$t<F> {
+            type Output = $t<F>;
+            fn $fn_name(self, rhs: F) -> $t<F> {
+                let mut res = self.clone();
+                std::ops::$opsassignname::$fnassign_name(&mut res, rhs);
+                res
+            }
+        }
+    };
+}
+
+// Implement the ops with a scalar `$f` on the left, from the ones with the scalar on the right
+macro_rules! scalar_lhs_ops_for {
+    ($t : ident<T, M, F>: $f : ty) => {
+        scalar_lhs_ops_for!(@impl [R: $crate::traits::RWAble<$f>] $t<R, $crate::RW, $f>, $f);
+    };
+    ($t : ident<F>: $f : ty) => {
+        scalar_lhs_ops_for!(@impl [] $t<$f>, $f);
+    };
+    (@impl [$($bounds : tt)*] $ty : ty, $f : ty) => {
+        impl<$($bounds)*> std::ops::Add<$ty> for $f {
+            type Output = $ty;
+            fn add(self, rhs: $ty) -> $ty {
+                rhs + self
+            }
+        }
+
+        impl<$($bounds)*> std::ops::Mul<$ty> for $f {
+            type Output = $ty;
+            fn mul(self, rhs: $ty) -> $ty {
+                rhs * self
+            }
+        }
+
+        impl<$($bounds)*> std::ops::Sub<$ty> for $f {
+            type Output = $ty;
+            fn sub(self, rhs: $ty) -> $ty {
+                -rhs + self
+            }
+        }
+
+        impl<$($bounds)*> std::ops::Div<$ty> for $f {
+            type Output = $ty;
+            fn div(self, rhs: $ty) -> $ty {
+                let mut res = rhs.inv();
+                std::ops::MulAssign::mul_assign(&mut res, self);
+                res
+            }
+        }
+    };
+}
@@ -0,0 +1,296 @@
+//! Non-smooth functions over `Dual`s, and the policy deciding their derivatives at kinks.
+
+use super::{CompatibleWith, Dual, OwningMode, ROAble, RWAble, Scalar, RW};
+use core::cmp::Ordering;
+
+/// The policy used to choose the derivative of a non-smooth function where it is not differentiable.
+///
+/// At kinks, such as 0 for `abs`, the derivative can be taken from either side
+/// (`Left` or `Right`), as their `Average`, or be replaced by `Zero` or `NaN`.
+/// Where the function is differentiable, the policy has no effect.
+///
+/// At discontinuities, such as integers for `floor`, the function is locally constant on both sides:
+/// all policies but `NaN` give a null derivative.
+///
+/// ```
+/// # use fwd_ad::*;
+/// let x = Dual::<_, RW, f64>::from([0., 1.]);
+/// assert_eq!(x.abs_with(KinkPolicy::Left).diffs(), &[-1.]);
+/// assert_eq!(x.abs_with(KinkPolicy::Right).diffs(), &[1.]);
+/// assert_eq!(x.abs_with(KinkPolicy::Average).diffs(), &[0.]);
+/// assert!(x.abs_with(KinkPolicy::NaN).diffs()[0].is_nan());
+/// assert_eq!(x.floor_with(KinkPolicy::Right).diffs(), &[0.]);
+/// ```
+#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash)]
+pub enum KinkPolicy {
+    /// The derivative from the left.
+    Left,
+    /// The derivative from the right.
+    Right,
+    /// The average of the derivatives from the left and from the right.
+    Average,
+    /// A null derivative.
+    Zero,
+    /// A NaN derivative.
+    NaN,
+}
+
+impl KinkPolicy {
+    /// Returns the derivative according to the policy, given the one-sided derivatives `left` and `right`.
+    fn select<F: Scalar>(self, left: F, right: F) -> F {
+        if left == right {
+            return left;
+        }
+        match self {
+            KinkPolicy::Left => left,
+            KinkPolicy::Right => right,
+            KinkPolicy::Average => (left + right) / (F::one() + F::one()),
+            KinkPolicy::Zero => F::zero(),
+            KinkPolicy::NaN => F::zero() / F::zero(),
+        }
+    }
+
+    /// Returns the derivative according to the policy for a step function,
+    /// `at_jump` indicating whether it is evaluated at one of its discontinuities.
+    fn step<F: Scalar>(self, at_jump: bool) -> F {
+        if at_jump && self == KinkPolicy::NaN {
+            F::zero() / F::zero()
+        } else {
+            F::zero()
+        }
+    }
+}
+
+// Whether `z` is neither NaN nor ordered with 0, as a `Pattern` which may be nonzero.
+// Such a quantity carries no magnitude, so both sides of a kink along it may be taken.
+fn is_unordered<F: Scalar>(z: F) -> bool {
+    z.partial_cmp(&z).is_some() && z.partial_cmp(&F::zero()).is_none()
+}
+
+/// Non-smooth functions
+impl<T, F> Dual<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    /// Returns |self|, using `policy` to choose the derivative at 0.
+    pub fn abs_with(self, policy: KinkPolicy) -> Self {
+        let v = self.val();
+        let left = if v > F::zero() { F::one() } else { -F::one() };
+        let right = if v < F::zero() { -F::one() } else { F::one() };
+        self.chain(v.abs(), policy.select(left, right))
+    }
+
+    /// Returns the minimum of self and `other`, using `policy` to choose the derivative when they are equal.
+    ///
+    /// The kink is considered along self - other: the derivative from the left is the one of self,
+    /// and the derivative from the right is the one of `other`.
+    /// When the difference carries no magnitude, as for [`Pattern`](struct.Pattern.html)s,
+    /// the derivatives of both are summed, whatever the policy.
+    pub fn min_with<S, M2>(self, other: Dual<S, M2, F>, policy: KinkPolicy) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        let z = self.val() - other.val();
+        if is_unordered(z) {
+            let val = self.val().min(other.val());
+            return self.merge(other, val);
+        }
+        let (one, zero) = (F::one(), F::zero());
+        let (ls, lo) = if z > zero { (zero, one) } else { (one, zero) };
+        let (rs, ro) = if z < zero { (one, zero) } else { (zero, one) };
+        self.combine(other, policy.select(ls, rs), policy.select(lo, ro))
+    }
+
+    /// Returns the maximum of self and `other`, using `policy` to choose the derivative when they are equal.
+    ///
+    /// The kink is considered along self - other: the derivative from the left is the one of `other`,
+    /// and the derivative from the right is the one of self.
+    /// When the difference carries no magnitude, as for [`Pattern`](struct.Pattern.html)s,
+    /// the derivatives of both are summed, whatever the policy.
+    pub fn max_with<S, M2>(self, other: Dual<S, M2, F>, policy: KinkPolicy) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        let z = self.val() - other.val();
+        if is_unordered(z) {
+            let val = self.val().max(other.val());
+            return self.merge(other, val);
+        }
+        let (one, zero) = (F::one(), F::zero());
+        let (ls, lo) = if z > zero { (one, zero) } else { (zero, one) };
+        let (rs, ro) = if z < zero { (zero, one) } else { (one, zero) };
+        self.combine(other, policy.select(ls, rs), policy.select(lo, ro))
+    }
+
+    /// Returns the sum of the diffs of self and `other`, with the value `val`.
+    fn merge<S, M2>(self, other: Dual<S, M2, F>, val: F) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        let mut res = self.combine(other, F::one(), F::one());
+        *res.val_mut() = val;
+        res
+    }
+
+    /// Returns `ws * self + wo * other`, assuming that the weights select
+    /// one of the values when they are not equal.
+    fn combine<S, M2>(mut self, other: Dual<S, M2, F>, ws: F, wo: F) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        assert_eq!(
+            self.ndiffs(),
+            other.ndiffs(),
+            "Duals have different numbers of diffs: {} =/= {}.",
+            self.ndiffs(),
+            other.ndiffs()
+        );
+        if wo == F::one() {
+            *self.val_mut() = other.val();
+        }
+        self.diffs_mut()
+            .iter_mut()
+            .zip(other.diffs())
+            .for_each(|(ds, dof)| *ds = ws * *ds + wo * *dof);
+        self
+    }
+
+    /// Returns self restricted to the interval [lo, hi], using `policy` to choose the derivative at its bounds.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let x = Dual::<_, RW, f64>::from([1., 1.]);
+    /// assert_eq!(x.clamp_with(0., 1., KinkPolicy::Left), x);
+    /// assert_eq!(x.clamp_with(0., 1., KinkPolicy::Right).diffs(), &[0.]);
+    /// assert_eq!(x.clamp_with(0., 2., KinkPolicy::Zero), x);
+    /// ```
+    pub fn clamp_with(self, lo: F, hi: F, policy: KinkPolicy) -> Self {
+        let v = self.val();
+        let left = if v > lo && v <= hi {
+            F::one()
+        } else {
+            F::zero()
+        };
+        let right = if v >= lo && v < hi {
+            F::one()
+        } else {
+            F::zero()
+        };
+        let dval = policy.select(left, right);
+        // At the bounds, the value follows the side chosen by the policy, as do its own derivatives for nested duals
+        let val = if dval == F::one() {
+            v
+        } else if v <= lo {
+            lo
+        } else if v >= hi {
+            hi
+        } else {
+            v
+        };
+        self.chain(val, dval)
+    }
+
+    /// Returns the sign of self, as `Real::signum`, using `policy` to choose the derivative at 0.
+    pub fn signum_with(self, policy: KinkPolicy) -> Self {
+        let v = self.val();
+        let at_zero = v.partial_cmp(&F::zero()) == Some(Ordering::Equal);
+        self.chain(v.signum(), policy.step(at_zero))
+    }
+
+    /// Returns the largest integer less than or equal to self, using `policy` to choose the derivative at integers.
+    pub fn floor_with(self, policy: KinkPolicy) -> Self {
+        let v = self.val();
+        let f = v.floor();
+        self.chain(f, policy.step(v == f))
+    }
+
+    /// Returns the smallest integer greater than or equal to self, using `policy` to choose the derivative at integers.
+    pub fn ceil_with(self, policy: KinkPolicy) -> Self {
+        let v = self.val();
+        let c = v.ceil();
+        self.chain(c, policy.step(v == c))
+    }
+
+    /// Returns the nearest integer to self, rounding half-way cases away from zero,
+    /// using `policy` to choose the derivative at half-way cases.
+    pub fn round_with(self, policy: KinkPolicy) -> Self {
+        let v = self.val();
+        let half = (F::one() + F::one()).recip();
+        self.chain(v.round(), policy.step(v.abs().fract() == half))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instanciations::vecf64::Owning;
+
+    fn policies() -> [KinkPolicy; 5] {
+        [
+            KinkPolicy::Left,
+            KinkPolicy::Right,
+            KinkPolicy::Average,
+            KinkPolicy::Zero,
+            KinkPolicy::NaN,
+        ]
+    }
+
+    #[test]
+    fn test_smooth_points() {
+        let x = Owning::from(vec![1.2, 1., -2.]);
+        let y = Owning::from(vec![-0.7, 3., 4.]);
+        for &p in policies().iter() {
+            assert_eq!(x.clone().abs_with(p), x.clone().abs());
+            assert_eq!(y.clone().abs_with(p), y.clone().abs());
+            assert_eq!(x.clone().min_with(y.view(), p), y);
+            assert_eq!(x.clone().max_with(y.view(), p), x);
+            assert_eq!(x.clone().clamp_with(0., 1., p), Owning::constant(1., 2));
+            assert_eq!(x.clone().clamp_with(0., 2., p), x);
+            assert_eq!(y.clone().signum_with(p), Owning::constant(-1., 2));
+            assert_eq!(x.clone().floor_with(p), Owning::constant(1., 2));
+            assert_eq!(x.clone().ceil_with(p), Owning::constant(2., 2));
+            assert_eq!(y.clone().round_with(p), Owning::constant(-1., 2));
+        }
+    }
+
+    #[test]
+    fn test_min_max_kink() {
+        let x = Owning::from(vec![1., 1., 0.]);
+        let y = Owning::from(vec![1., 0., 1.]);
+        let expected = [
+            (vec![1., 1., 0.], vec![1., 0., 1.]),
+            (vec![1., 0., 1.], vec![1., 1., 0.]),
+            (vec![1., 0.5, 0.5], vec![1., 0.5, 0.5]),
+            (vec![1., 0., 0.], vec![1., 0., 0.]),
+        ];
+        for (&p, (min, max)) in policies().iter().zip(expected.iter()) {
+            assert_eq!(x.clone().min_with(y.view(), p).as_slice(), &min[..]);
+            assert_eq!(x.clone().max_with(y.view(), p).as_slice(), &max[..]);
+        }
+        assert!(x.min_with(y, KinkPolicy::NaN).diffs()[0].is_nan());
+    }
+
+    #[test]
+    fn test_steps() {
+        let x = Owning::from(vec![2.5, 1.]);
+        assert_eq!(x.clone().round_with(KinkPolicy::Left).as_slice(), &[3., 0.]);
+        assert!(x.clone().round_with(KinkPolicy::NaN).diffs()[0].is_nan());
+        assert!(Owning::from(vec![-2., 1.])
+            .floor_with(KinkPolicy::NaN)
+            .diffs()[0]
+            .is_nan());
+        assert!(Owning::from(vec![0., 1.])
+            .signum_with(KinkPolicy::NaN)
+            .diffs()[0]
+            .is_nan());
+    }
+}
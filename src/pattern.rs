@@ -0,0 +1,433 @@
+//! A module containing `Pattern`, a scalar propagating which quantities may be nonzero rather than their values.
+//!
+//! Duals of `Pattern`s run through the same code as duals of floats,
+//! and their differentials tell which inputs each result depends on,
+//! which gives the sparsity pattern of a Jacobian without computing any number:
+//! see [`drivers::jacobian_pattern`](../drivers/fn.jacobian_pattern.html).
+//!
+//! ```
+//! # use fwd_ad::*;
+//! let t = Pattern::from(true);
+//! let x: Dual<_, RW, Pattern> = [t, t.into(), false.into()].into();
+//! let y: Dual<_, RW, Pattern> = [t, false.into(), t.into()].into();
+//! // x*y depends on both variables, x*0 on none.
+//! assert_eq!((x * y).diffs(), &[t, t]);
+//! assert_eq!((x * Pattern::from(false)).diffs(), &[Pattern::from(false); 2]);
+//! ```
+
+use num_traits::float::FloatConst;
+use num_traits::{Float, FromPrimitive, Num, NumCast, One, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::num::FpCategory;
+use std::ops;
+
+/// A scalar recording whether a quantity may be nonzero.
+///
+/// `Pattern(false)` is a quantity known to be exactly zero, and `Pattern(true)` one which may be nonzero.
+/// Arithmetic follows from this: a sum may be nonzero if either term may be, a product if both factors may be.
+/// Functions return `Pattern(true)`, unless they map zero to zero and their argument is known to be zero.
+/// All of this is conservative: a quantity found to be zero is zero for all values of the inputs.
+///
+/// Values carry no magnitude, so distinct `Pattern`s are unordered, and code branching on values,
+/// including the non-smooth functions, only follows one of its branches.
+/// With `KinkPolicy::Average`, the `*_with` non-smooth functions see every point as a kink,
+/// and merge the dependencies of both sides.
+/// `min_with` and `max_with`, and hence `Float::min` and `Float::max`, always merge them.
+///
+/// Literal constants converted through `NumCast` or `FromPrimitive` keep their zeroness,
+/// and `Pattern` implements `Scalar`, so it can back `Dual`s as well as `SparseDual`s,
+/// the latter propagating sets of indices.
+///
+/// ```
+/// # use fwd_ad::*;
+/// let t = Pattern::from(true);
+/// let x = SparseDual::from_pairs(t, vec![(3, t)]);
+/// let y = SparseDual::from_pairs(t, vec![(8, t)]);
+/// let z = (x.clone() * y).sin() + x.exp();
+/// assert_eq!(z.indices(), &[3, 8]);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, Default)]
+pub struct Pattern(pub bool);
+
+impl Pattern {
+    /// Returns true if the quantity may be nonzero.
+    pub fn is_nonzero(self) -> bool {
+        self.0
+    }
+}
+
+// Only equal patterns are comparable
+impl PartialOrd for Pattern {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<bool> for Pattern {
+    fn from(nonzero: bool) -> Self {
+        Pattern(nonzero)
+    }
+}
+
+impl From<Pattern> for bool {
+    fn from(p: Pattern) -> bool {
+        p.0
+    }
+}
+
+//
+//
+// Arithmetic
+// ==========
+//
+//
+
+// Derive the ops by value and by reference, and their assigning versions, from a function on bools
+macro_rules! pattern_ops {
+    ($opsname : ident, $opsassignname : ident, $fn_name:ident, $fnassign_name : ident, $f : expr) => {
+        impl ops::$opsname<Pattern> for Pattern {
+            type Output = Pattern;
+            fn $fn_name(self, rhs: Pattern) -> Pattern {
+                let f: fn(bool, bool) -> bool = $f;
+                Pattern(f(self.0, rhs.0))
+            }
+        }
+
+        impl ops::$opsname<&Pattern> for Pattern {
+            type Output = Pattern;
+            fn $fn_name(self, rhs: &Pattern) -> Pattern {
+                ops::$opsname::$fn_name(self, *rhs)
+            }
+        }
+
+        impl ops::$opsassignname<Pattern> for Pattern {
+            fn $fnassign_name(&mut self, rhs: Pattern) {
+                *self = ops::$opsname::$fn_name(*self, rhs);
+            }
+        }
+
+        impl ops::$opsassignname<&Pattern> for Pattern {
+            fn $fnassign_name(&mut self, rhs: &Pattern) {
+                *self = ops::$opsname::$fn_name(*self, *rhs);
+            }
+        }
+    };
+}
+
+pattern_ops!(Add, AddAssign, add, add_assign, |a, b| a || b);
+pattern_ops!(Sub, SubAssign, sub, sub_assign, |a, b| a || b);
+pattern_ops!(Mul, MulAssign, mul, mul_assign, |a, b| a && b);
+// A division by zero gives an infinity or a NaN
+pattern_ops!(Div, DivAssign, div, div_assign, |a, b| a || !b);
+pattern_ops!(Rem, RemAssign, rem, rem_assign, |a, b| a || !b);
+
+impl ops::Neg for Pattern {
+    type Output = Pattern;
+    fn neg(self) -> Pattern {
+        self
+    }
+}
+
+//
+//
+// num_traits
+// ==========
+//
+//
+
+impl Zero for Pattern {
+    fn zero() -> Self {
+        Pattern(false)
+    }
+
+    fn is_zero(&self) -> bool {
+        !self.0
+    }
+}
+
+impl One for Pattern {
+    fn one() -> Self {
+        Pattern(true)
+    }
+}
+
+impl Num for Pattern {
+    type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        f64::from_str_radix(str, radix).map(|x| Pattern(x != 0.))
+    }
+}
+
+macro_rules! float_consts {
+    ($($name : ident),*) => {
+        // All the constants are nonzero.
+        impl FloatConst for Pattern {
+            $(
+                fn $name() -> Self {
+                    Pattern(true)
+                }
+            )*
+        }
+    };
+}
+
+float_consts!(
+    E,
+    FRAC_1_PI,
+    FRAC_1_SQRT_2,
+    FRAC_2_PI,
+    FRAC_2_SQRT_PI,
+    FRAC_PI_2,
+    FRAC_PI_3,
+    FRAC_PI_4,
+    FRAC_PI_6,
+    FRAC_PI_8,
+    LN_10,
+    LN_2,
+    LOG10_E,
+    LOG2_E,
+    PI,
+    SQRT_2
+);
+
+// A nonzero quantity is converted to 1
+impl ToPrimitive for Pattern {
+    fn to_i64(&self) -> Option<i64> {
+        Some(self.0 as i64)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        Some(self.0 as u64)
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(if self.0 { 1. } else { 0. })
+    }
+}
+
+impl NumCast for Pattern {
+    fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+        n.to_f64().map(|x| Pattern(x != 0.))
+    }
+}
+
+impl FromPrimitive for Pattern {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(Pattern(n != 0))
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(Pattern(n != 0))
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        Some(Pattern(n != 0.))
+    }
+}
+
+// Functions mapping zero to zero
+macro_rules! keep_zero {
+    ($($name : ident),*) => {
+        $(
+            fn $name(self) -> Self {
+                self
+            }
+        )*
+    };
+}
+
+// Functions which may be nonzero at zero
+macro_rules! nonzero {
+    ($($name : ident),*) => {
+        $(
+            fn $name(self) -> Self {
+                Pattern(true)
+            }
+        )*
+    };
+}
+
+impl Float for Pattern {
+    fn nan() -> Self {
+        Pattern(true)
+    }
+
+    fn infinity() -> Self {
+        Pattern(true)
+    }
+
+    fn neg_infinity() -> Self {
+        Pattern(true)
+    }
+
+    fn neg_zero() -> Self {
+        Pattern(false)
+    }
+
+    fn min_value() -> Self {
+        Pattern(true)
+    }
+
+    fn min_positive_value() -> Self {
+        Pattern(true)
+    }
+
+    fn max_value() -> Self {
+        Pattern(true)
+    }
+
+    fn epsilon() -> Self {
+        Pattern(true)
+    }
+
+    fn is_nan(self) -> bool {
+        false
+    }
+
+    fn is_infinite(self) -> bool {
+        false
+    }
+
+    fn is_finite(self) -> bool {
+        true
+    }
+
+    fn is_normal(self) -> bool {
+        self.0
+    }
+
+    fn classify(self) -> FpCategory {
+        if self.0 {
+            FpCategory::Normal
+        } else {
+            FpCategory::Zero
+        }
+    }
+
+    fn is_sign_positive(self) -> bool {
+        true
+    }
+
+    fn is_sign_negative(self) -> bool {
+        false
+    }
+
+    fn integer_decode(self) -> (u64, i16, i8) {
+        Float::integer_decode(if self.0 { 1_f64 } else { 0_f64 })
+    }
+
+    keep_zero!(
+        floor, ceil, round, trunc, fract, abs, sqrt, cbrt, sin, tan, asin, atan, sinh, tanh, asinh,
+        atanh, exp_m1, ln_1p, to_degrees, to_radians
+    );
+
+    // signum(0.) is 1
+    nonzero!(recip, exp, exp2, ln, log2, log10, cos, acos, cosh, acosh, signum);
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        self * a + b
+    }
+
+    fn powi(self, n: i32) -> Self {
+        if n > 0 {
+            self
+        } else {
+            Pattern(true)
+        }
+    }
+
+    fn powf(self, _n: Self) -> Self {
+        Pattern(true)
+    }
+
+    fn log(self, _base: Self) -> Self {
+        Pattern(true)
+    }
+
+    fn max(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn min(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn abs_sub(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        (self, Pattern(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dual, KinkPolicy, RW};
+
+    const T: Pattern = Pattern(true);
+    const Z: Pattern = Pattern(false);
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(T + Z, T);
+        assert_eq!(Z - Z, Z);
+        assert_eq!(T * Z, Z);
+        assert_eq!(T * T, T);
+        assert_eq!(Z / T, Z);
+        assert_eq!(Z / Z, T);
+        assert_eq!(T % T, T);
+        assert_eq!(-T, T);
+        let mut p = Z;
+        p += T;
+        p *= &Z;
+        assert_eq!(p, Z);
+        assert_eq!(<Pattern as NumCast>::from(0.).unwrap(), Z);
+        assert_eq!(<Pattern as NumCast>::from(2).unwrap(), T);
+        assert_eq!(Pattern::from_str_radix("0", 10).unwrap(), Z);
+        assert_eq!(Pattern::PI(), T);
+        assert_eq!(Z.sin(), Z);
+        assert_eq!(Z.cos(), T);
+        assert_eq!(Z.powi(2), Z);
+        assert_eq!(Z.signum(), T);
+        assert_eq!(Z.powi(-1), T);
+        assert_eq!(T.partial_cmp(&Z), None);
+        assert!(T <= T);
+    }
+
+    #[test]
+    fn test_dual() {
+        // x0 and x1 are variables, c is a constant
+        let x0: Dual<_, RW, Pattern> = [T, T, Z, Z].into();
+        let x1: Dual<_, RW, Pattern> = [T, Z, T, Z].into();
+        let c: Dual<_, RW, Pattern> = [T, Z, Z, Z].into();
+        assert_eq!((x0 * x1.view()).diffs(), &[T, T, Z]);
+        assert_eq!((x0 / x1.view()).diffs(), &[T, T, Z]);
+        assert_eq!((x0.exp() * c.view()).diffs(), &[T, Z, Z]);
+        assert_eq!((c / x1.view()).diffs(), &[Z, T, Z]);
+        assert_eq!(x0.powi(3).atan2(c).diffs(), &[T, Z, Z]);
+        assert_eq!((x0.sin() * Z).diffs(), &[Z, Z, Z]);
+        assert_eq!(x1.floor().diffs(), &[Z, Z, Z]);
+        assert_eq!(x0.max_with(x1, KinkPolicy::Average).diffs(), &[T, T, Z]);
+        assert_eq!(x0.min_with(x1, KinkPolicy::Left).diffs(), &[T, T, Z]);
+        assert_eq!(Float::max(x0, x1).diffs(), &[T, T, Z]);
+        assert_eq!(Float::min(c, x1).diffs(), &[Z, T, Z]);
+        // The value of the result may be nonzero if either one may be
+        let z: Dual<_, RW, Pattern> = [Z, Z, Z, T].into();
+        assert_eq!(Float::max(z, c).val(), T);
+    }
+}
@@ -0,0 +1,231 @@
+//! Values and derivatives of the elementary functions.
+//!
+//! They are shared by the dual types whose differentials are all multiplied by the derivative
+//! of the function applied, `Dual` and `SparseDual`, which pass them to their `chain` method.
+//! Functions of several arguments return the partial derivatives with respect to each of them.
+
+use crate::special::{erf, gamma};
+use crate::Scalar;
+
+pub(crate) use crate::special::bessel::{bessel_i, bessel_j, bessel_k, bessel_y};
+
+/// Multiplies the differentials by `dval`.
+///
+/// If `dval` is infinite, null differentials are kept null instead of becoming NaN.
+pub(crate) fn scale<F: Scalar>(diffs: &mut [F], dval: F) {
+    if dval.abs() > F::max_value() {
+        for x in diffs {
+            if *x != F::zero() {
+                *x *= dval;
+            }
+        }
+    } else {
+        for x in diffs {
+            *x *= dval;
+        }
+    }
+}
+
+pub(crate) fn exp<F: Scalar>(v: F) -> (F, F) {
+    let e = v.exp();
+    (e, e)
+}
+
+pub(crate) fn exp2<F: Scalar>(v: F) -> (F, F) {
+    let e = v.exp2();
+    (e, F::LN_2() * e)
+}
+
+pub(crate) fn exp_m1<F: Scalar>(v: F) -> (F, F) {
+    let e = v.exp_m1();
+    (e, e + F::one())
+}
+
+pub(crate) fn ln<F: Scalar>(v: F) -> (F, F) {
+    (v.ln(), v.recip())
+}
+
+pub(crate) fn ln_1p<F: Scalar>(v: F) -> (F, F) {
+    (v.ln_1p(), (F::one() + v).recip())
+}
+
+pub(crate) fn log2<F: Scalar>(v: F) -> (F, F) {
+    (v.log2(), (v * F::LN_2()).recip())
+}
+
+pub(crate) fn log10<F: Scalar>(v: F) -> (F, F) {
+    (v.log10(), (v * F::LN_10()).recip())
+}
+
+pub(crate) fn log<F: Scalar>(v: F, base: F) -> (F, F) {
+    (v.log(base), (v * base.ln()).recip())
+}
+
+pub(crate) fn inv<F: Scalar>(v: F) -> (F, F) {
+    let r = v.recip();
+    (r, -r * r)
+}
+
+pub(crate) fn powf<F: Scalar>(v: F, exp: F) -> (F, F) {
+    (v.powf(exp), exp * v.powf(exp - F::one()))
+}
+
+/// The derivative is exact, including at 0 for positive exponents.
+pub(crate) fn powi<F: Scalar>(v: F, n: i32) -> (F, F) {
+    let dval = if n == 0 {
+        F::zero()
+    } else {
+        F::from(n).unwrap() * v.powi(n - 1)
+    };
+    (v.powi(n), dval)
+}
+
+pub(crate) fn sqrt<F: Scalar>(v: F) -> (F, F) {
+    let s = v.sqrt();
+    (s, (s + s).recip())
+}
+
+pub(crate) fn cbrt<F: Scalar>(v: F) -> (F, F) {
+    let c = v.cbrt();
+    (c, (F::from(3).unwrap() * c * c).recip())
+}
+
+pub(crate) fn recip_sqrt<F: Scalar>(v: F) -> (F, F) {
+    let r = v.sqrt().recip();
+    (r, -r * r * r / F::from(2).unwrap())
+}
+
+pub(crate) fn sin<F: Scalar>(v: F) -> (F, F) {
+    v.sin_cos()
+}
+
+pub(crate) fn cos<F: Scalar>(v: F) -> (F, F) {
+    let (s, c) = v.sin_cos();
+    (c, -s)
+}
+
+pub(crate) fn tan<F: Scalar>(v: F) -> (F, F) {
+    let t = v.tan();
+    (t, F::one() + t * t)
+}
+
+pub(crate) fn asin<F: Scalar>(v: F) -> (F, F) {
+    (v.asin(), (F::one() - v * v).sqrt().recip())
+}
+
+pub(crate) fn acos<F: Scalar>(v: F) -> (F, F) {
+    (v.acos(), -(F::one() - v * v).sqrt().recip())
+}
+
+pub(crate) fn atan<F: Scalar>(v: F) -> (F, F) {
+    (v.atan(), (F::one() + v * v).recip())
+}
+
+pub(crate) fn sinh<F: Scalar>(v: F) -> (F, F) {
+    (v.sinh(), v.cosh())
+}
+
+pub(crate) fn cosh<F: Scalar>(v: F) -> (F, F) {
+    (v.cosh(), v.sinh())
+}
+
+pub(crate) fn tanh<F: Scalar>(v: F) -> (F, F) {
+    let t = v.tanh();
+    (t, F::one() - t * t)
+}
+
+pub(crate) fn asinh<F: Scalar>(v: F) -> (F, F) {
+    (v.asinh(), (v * v + F::one()).sqrt().recip())
+}
+
+pub(crate) fn acosh<F: Scalar>(v: F) -> (F, F) {
+    (v.acosh(), (v * v - F::one()).sqrt().recip())
+}
+
+pub(crate) fn atanh<F: Scalar>(v: F) -> (F, F) {
+    // Going through sqrt makes the derivative NaN outside of the domain
+    let s = (F::one() - v * v).sqrt();
+    (v.atanh(), (s * s).recip())
+}
+
+/// The partial derivatives are null at the origin.
+pub(crate) fn hypot<F: Scalar>(v: F, w: F) -> (F, F, F) {
+    let h = v.hypot(w);
+    if h == F::zero() {
+        (h, F::zero(), F::zero())
+    } else {
+        (h, v / h, w / h)
+    }
+}
+
+/// The partial derivatives are null at the origin.
+pub(crate) fn atan2<F: Scalar>(v: F, w: F) -> (F, F, F) {
+    let r2 = v * v + w * w;
+    if r2 == F::zero() {
+        (v.atan2(w), F::zero(), F::zero())
+    } else {
+        (v.atan2(w), w / r2, -v / r2)
+    }
+}
+
+/// A null base is kept, with null partial derivatives.
+pub(crate) fn powdual<F: Scalar>(v: F, exp: F) -> (F, F, F) {
+    if v == F::zero() {
+        return (v, F::zero(), F::zero());
+    }
+    let p = v.powf(exp - F::one());
+    (v.powf(exp), exp * p, v * p * v.ln())
+}
+
+pub(crate) fn gamma<F: Scalar>(v: F) -> (F, F) {
+    let g = gamma::gamma(v);
+    (g, g * gamma::digamma(v))
+}
+
+pub(crate) fn ln_gamma<F: Scalar>(v: F) -> (F, F) {
+    (gamma::ln_gamma(v), gamma::digamma(v))
+}
+
+pub(crate) fn digamma<F: Scalar>(v: F) -> (F, F) {
+    (gamma::digamma(v), gamma::trigamma(v))
+}
+
+/// Returns P(a, v) and its partial derivatives with respect to v and a.
+pub(crate) fn gamma_p<F: Scalar>(a: F, v: F) -> (F, F, F) {
+    let p = gamma::inc_gamma(a, v, false);
+    (p.val(), gamma::inc_gamma_dx(a, v), p.diffs()[0])
+}
+
+/// Returns Q(a, v) and its partial derivatives with respect to v and a.
+pub(crate) fn gamma_q<F: Scalar>(a: F, v: F) -> (F, F, F) {
+    let q = gamma::inc_gamma(a, v, true);
+    (q.val(), -gamma::inc_gamma_dx(a, v), q.diffs()[0])
+}
+
+/// Returns I_v(a, b) and its partial derivatives with respect to v, a and b.
+pub(crate) fn beta_inc<F: Scalar>(a: F, b: F, v: F) -> (F, F, F, F) {
+    let i = gamma::inc_beta(a, b, v);
+    (
+        i.val(),
+        gamma::inc_beta_dx(a, b, v),
+        i.diffs()[0],
+        i.diffs()[1],
+    )
+}
+
+pub(crate) fn erf<F: Scalar>(v: F) -> (F, F) {
+    (erf::erf(v), F::FRAC_2_SQRT_PI() * (-v * v).exp())
+}
+
+pub(crate) fn erfc<F: Scalar>(v: F) -> (F, F) {
+    (erf::erfc(v), -F::FRAC_2_SQRT_PI() * (-v * v).exp())
+}
+
+pub(crate) fn normal_cdf<F: Scalar>(v: F) -> (F, F) {
+    (erf::normal_cdf(v), erf::normal_pdf(v))
+}
+
+pub(crate) fn probit<F: Scalar>(v: F) -> (F, F) {
+    let x = erf::probit(v);
+    (x, erf::normal_pdf(x).recip())
+}
@@ -0,0 +1,641 @@
+//! A module containing dual numbers with sparse differentials.
+//!
+//! `Dual`s store their differentials densely, so that every intermediate result carries
+//! as many differentials as there are variables, even when it only depends on a few of them.
+//! A `SparseDual` instead stores the differentials of the variables it depends on,
+//! as pairs of sorted indices and values, and operations between them merge the index sets.
+//!
+//! The container traits give access to the content as a dense slice, which a sparse storage cannot provide,
+//! so `SparseDual` is a standalone type rather than a container for `Dual`.
+//! Its functions nonetheless share their values and derivatives with those of `Dual`.
+//!
+//! ```
+//! # use fwd_ad::*;
+//! // Only the variables involved are stored, among a million.
+//! let x = SparseDual::variable(2., 10);
+//! let y = SparseDual::variable(3., 999_999);
+//! let z = x.clone() * &y + x.sin();
+//! assert_eq!(z.indices(), &[10, 999_999]);
+//! assert_eq!(z.diff(10), 3. + 2_f64.cos());
+//! assert_eq!(z.diff(999_999), 2.);
+//! assert_eq!(z.diff(0), 0.);
+//! ```
+
+use super::{rules, CompatibleWith, Dual, OwningMode, ROAble, Scalar, RW};
+use std::cmp::Ordering;
+use std::ops;
+
+// Defines functions of a single variable, whose values and derivatives are given by `rules`
+macro_rules! chain_rule_fns {
+    ($($name : ident : $doc : literal,)*) => {
+        $(
+            #[doc = $doc]
+            pub fn $name(self) -> Self {
+                let (val, dval) = rules::$name(self.val);
+                self.chain(val, dval)
+            }
+        )*
+    };
+}
+
+/// The struct implementing dual numbers with sparse differentials.
+///
+/// The differentials are stored as sorted, distinct indices of variables and their values.
+/// Absent indices stand for null differentials,
+/// but null differentials which result from computations are kept, so that comparisons with `==` are structural:
+/// use `is_close` to compare values and differentials.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct SparseDual<F>
+where
+    F: Scalar,
+{
+    val: F,
+    indices: Vec<usize>,
+    diffs: Vec<F>,
+}
+
+impl<F> SparseDual<F>
+where
+    F: Scalar,
+{
+    /// Generates a sparse dual with value `value` and no differentials.
+    pub fn constant(value: F) -> Self {
+        SparseDual {
+            val: value,
+            indices: Vec::new(),
+            diffs: Vec::new(),
+        }
+    }
+
+    /// Generates the variable of index `index`, with value `value`: its only differential is 1, at `index`.
+    pub fn variable(value: F, index: usize) -> Self {
+        SparseDual {
+            val: value,
+            indices: vec![index],
+            diffs: vec![F::one()],
+        }
+    }
+
+    /// Generates a sparse dual from its value and pairs of indices and differentials.
+    ///
+    /// The pairs are sorted by index, and the differentials of repeated indices are summed.
+    pub fn from_pairs<I>(value: F, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, F)>,
+    {
+        let mut pairs: Vec<(usize, F)> = pairs.into_iter().collect();
+        pairs.sort_by_key(|&(i, _)| i);
+        let mut res = Self::constant(value);
+        for (i, d) in pairs {
+            if res.indices.last() == Some(&i) {
+                *res.diffs.last_mut().unwrap() += d;
+            } else {
+                res.indices.push(i);
+                res.diffs.push(d);
+            }
+        }
+        res
+    }
+
+    /// Generates a sparse dual from a `Dual`, keeping its non-null differentials.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let d = Dual::<_, RW, f64>::from(vec![1., 0., 2., 0.]);
+    /// let s = SparseDual::from_dual(&d);
+    /// assert_eq!(s.indices(), &[1]);
+    /// assert_eq!(s.to_dual(3), d);
+    /// ```
+    pub fn from_dual<T, M>(dual: &Dual<T, M, F>) -> Self
+    where
+        M: OwningMode,
+        T: ROAble<F>,
+        T: CompatibleWith<M, F>,
+    {
+        Self::from_pairs(
+            dual.val(),
+            dual.diffs()
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| **d != F::zero())
+                .map(|(i, d)| (i, *d)),
+        )
+    }
+
+    /// Returns a `Dual` backed by a `Vec`, with `ndiffs` dense differentials.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index of self is not smaller than `ndiffs`.
+    pub fn to_dual(&self, ndiffs: usize) -> Dual<Vec<F>, RW, F> {
+        let mut res = Dual::<Vec<F>, RW, F>::constant(self.val, ndiffs);
+        for (i, d) in self.iter() {
+            res.diffs_mut()[i] = d;
+        }
+        res
+    }
+
+    /// Returns the value.
+    pub fn val(&self) -> F {
+        self.val
+    }
+
+    /// Returns a mutable reference to the value.
+    pub fn val_mut(&mut self) -> &mut F {
+        &mut self.val
+    }
+
+    /// Returns the sorted indices of the stored differentials.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns the stored differentials, in the order of `indices`.
+    pub fn diffs(&self) -> &[F] {
+        &self.diffs
+    }
+
+    /// Returns the stored differentials as a mutable slice, in the order of `indices`.
+    pub fn diffs_mut(&mut self) -> &mut [F] {
+        &mut self.diffs
+    }
+
+    /// Returns the number of stored differentials.
+    pub fn nnz(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns the differential with respect to the variable of index `index`, 0 if it is not stored.
+    pub fn diff(&self, index: usize) -> F {
+        match self.indices.binary_search(&index) {
+            Ok(k) => self.diffs[k],
+            Err(_) => F::zero(),
+        }
+    }
+
+    /// Iterates over the stored differentials, as pairs of indices and values.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, F)> + '_ {
+        self.indices.iter().copied().zip(self.diffs.iter().copied())
+    }
+
+    /// Checks that the values and all the differentials of self and `b` are within `atol` of each other.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let a = SparseDual::from_pairs(1., vec![(0, 1.), (2, 0.)]);
+    /// let b = SparseDual::from_pairs(1., vec![(0, 1. + 1e-10)]);
+    /// assert!(a != b);
+    /// assert!(a.is_close(&b, 1e-9));
+    /// ```
+    pub fn is_close(&self, b: &Self, atol: F) -> bool {
+        let mut res = self.clone();
+        res.combine(F::one(), b, -F::one());
+        (self.val - b.val).abs() <= atol && res.diffs.iter().all(|d| d.abs() <= atol)
+    }
+
+    // Sets the differentials of self to alpha * self + beta * other, merging the index sets.
+    fn combine(&mut self, alpha: F, other: &Self, beta: F) {
+        if self.indices == other.indices {
+            for (ds, dr) in self.diffs.iter_mut().zip(&other.diffs) {
+                *ds = alpha * *ds + beta * *dr;
+            }
+            return;
+        }
+        let capacity = self.nnz() + other.nnz();
+        let mut indices = Vec::with_capacity(capacity);
+        let mut diffs = Vec::with_capacity(capacity);
+        let (mut a, mut b) = (0, 0);
+        while a < self.nnz() || b < other.nnz() {
+            let order = match (self.indices.get(a), other.indices.get(b)) {
+                (Some(i), Some(j)) => i.cmp(j),
+                (Some(_), None) => Ordering::Less,
+                _ => Ordering::Greater,
+            };
+            match order {
+                Ordering::Less => {
+                    indices.push(self.indices[a]);
+                    diffs.push(alpha * self.diffs[a]);
+                    a += 1;
+                }
+                Ordering::Greater => {
+                    indices.push(other.indices[b]);
+                    diffs.push(beta * other.diffs[b]);
+                    b += 1;
+                }
+                Ordering::Equal => {
+                    indices.push(self.indices[a]);
+                    diffs.push(alpha * self.diffs[a] + beta * other.diffs[b]);
+                    a += 1;
+                    b += 1;
+                }
+            }
+        }
+        self.indices = indices;
+        self.diffs = diffs;
+    }
+
+    // Sets the value to `val` and multiplies the differentials by `dval`,
+    // the derivative of the function applied to self, as `Dual::chain` does.
+    fn chain(mut self, val: F, dval: F) -> Self {
+        self.val = val;
+        rules::scale(&mut self.diffs, dval);
+        self
+    }
+
+    // As `chain`, then adds `dparam` times the differentials of `param`, a parameter of the function.
+    fn chain_with(self, val: F, dval: F, param: &Self, dparam: F) -> Self {
+        let mut res = self.chain(val, dval);
+        res.combine(F::one(), param, dparam);
+        res
+    }
+
+    chain_rule_fns! {
+        exp: "Returns e^self.",
+        exp2: "Returns 2^self.",
+        ln: "Returns ln(self).",
+        log2: "Returns log2(self).",
+        log10: "Returns log10(self).",
+        inv: "Returns 1/self.",
+        sqrt: "Returns sqrt(self).",
+        cbrt: "Returns cbrt(self).",
+        sin: "Returns sin(self).",
+        cos: "Returns cos(self).",
+        tan: "Returns tan(self).",
+        asin: "Returns asin(self).",
+        acos: "Returns acos(self).",
+        atan: "Returns atan(self).",
+        sinh: "Returns sinh(self).",
+        cosh: "Returns cosh(self).",
+        tanh: "Returns tanh(self).",
+        asinh: "Returns asinh(self).",
+        acosh: "Returns acosh(self).",
+        atanh: "Returns atanh(self).",
+        ln_1p: "Returns ln(1+self), more accurately than if the operations were performed separately.",
+        exp_m1: "Returns e^self - 1, more accurately than if the operations were performed separately.",
+        recip_sqrt: "Returns 1/sqrt(self).",
+        gamma: "Returns Γ(self), the gamma function.",
+        ln_gamma: "Returns ln|Γ(self)|, the logarithm of the absolute value of the gamma function.",
+        digamma: "Returns ψ(self), the digamma function, which is the derivative of `ln_gamma`.",
+        erf: "Returns erf(self), the error function.",
+        erfc: "Returns erfc(self) = 1 - erf(self), the complementary error function.",
+        normal_cdf: "Returns Φ(self), the cumulative distribution function of the standard normal distribution.",
+        probit: "Returns Φ⁻¹(self), the quantile function of the standard normal distribution.",
+    }
+
+    /// Returns log_base(self).
+    pub fn log(self, base: F) -> Self {
+        let (val, dval) = rules::log(self.val, base);
+        self.chain(val, dval)
+    }
+
+    /// Returns self^exp.
+    pub fn powf(self, exp: F) -> Self {
+        let (val, dval) = rules::powf(self.val, exp);
+        self.chain(val, dval)
+    }
+
+    /// Returns self^n.
+    pub fn powi(self, n: i32) -> Self {
+        let (val, dval) = rules::powi(self.val, n);
+        self.chain(val, dval)
+    }
+
+    /// Returns |self|.
+    ///
+    /// At 0, the derivatives of the positive branch are used, as for `Dual::abs`.
+    pub fn abs(self) -> Self {
+        if self.val < F::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Returns self^exp, where the exponent is itself a sparse dual.
+    pub fn powdual(mut self, exp: &Self) -> Self {
+        let (val, dbase, dexp) = rules::powdual(self.val, exp.val);
+        self.val = val;
+        self.combine(dbase, exp, dexp);
+        self
+    }
+
+    /// Returns self * a + b, merging the index sets of the three.
+    pub fn mul_add(mut self, a: &Self, b: &Self) -> Self {
+        let (vs, va) = (self.val, a.val);
+        self.val = vs.mul_add(va, b.val);
+        self.combine(va, a, vs);
+        self.combine(F::one(), b, F::one());
+        self
+    }
+
+    /// Returns sqrt(self^2 + other^2).
+    ///
+    /// At the origin, the differentials are null, as for `Dual::hypot`.
+    pub fn hypot(mut self, other: &Self) -> Self {
+        let (val, wself, wother) = rules::hypot(self.val, other.val);
+        self.val = val;
+        self.combine(wself, other, wother);
+        self
+    }
+
+    /// Returns atan2(self, other), the four quadrant arctangent of self / other.
+    ///
+    /// At the origin, the differentials are null, as for `Dual::atan2`.
+    pub fn atan2(mut self, other: &Self) -> Self {
+        let (val, wself, wother) = rules::atan2(self.val, other.val);
+        self.val = val;
+        self.combine(wself, other, wother);
+        self
+    }
+
+    /// Returns J_n(self), the Bessel function of the first kind of integer order `n`.
+    pub fn bessel_j(self, n: i32) -> Self {
+        let (val, dval) = rules::bessel_j(n, self.val);
+        self.chain(val, dval)
+    }
+
+    /// Returns Y_n(self), the Bessel function of the second kind of integer order `n`.
+    pub fn bessel_y(self, n: i32) -> Self {
+        let (val, dval) = rules::bessel_y(n, self.val);
+        self.chain(val, dval)
+    }
+
+    /// Returns I_ν(self), the modified Bessel function of the first kind of real order `nu`.
+    pub fn bessel_i(self, nu: F) -> Self {
+        let (val, dval, _) = rules::bessel_i(nu, self.val);
+        self.chain(val, dval)
+    }
+
+    /// Returns I_ν(self), where the order ν is itself a sparse dual.
+    pub fn bessel_i_dual(self, nu: &Self) -> Self {
+        let (val, dval, dnu) = rules::bessel_i(nu.val, self.val);
+        self.chain_with(val, dval, nu, dnu)
+    }
+
+    /// Returns K_ν(self), the modified Bessel function of the second kind of real order `nu`.
+    pub fn bessel_k(self, nu: F) -> Self {
+        let (val, dval, _) = rules::bessel_k(nu, self.val);
+        self.chain(val, dval)
+    }
+
+    /// Returns K_ν(self), where the order ν is itself a sparse dual.
+    pub fn bessel_k_dual(self, nu: &Self) -> Self {
+        let (val, dval, dnu) = rules::bessel_k(nu.val, self.val);
+        self.chain_with(val, dval, nu, dnu)
+    }
+
+    /// Returns P(a, self), the regularized lower incomplete gamma function.
+    pub fn gamma_p(self, a: &Self) -> Self {
+        let (val, dval, da) = rules::gamma_p(a.val, self.val);
+        self.chain_with(val, dval, a, da)
+    }
+
+    /// Returns Q(a, self) = 1 - P(a, self), the regularized upper incomplete gamma function.
+    pub fn gamma_q(self, a: &Self) -> Self {
+        let (val, dval, da) = rules::gamma_q(a.val, self.val);
+        self.chain_with(val, dval, a, da)
+    }
+
+    /// Returns I_self(a, b), the regularized incomplete beta function.
+    pub fn beta_inc(self, a: &Self, b: &Self) -> Self {
+        let (val, dval, da, db) = rules::beta_inc(a.val, b.val, self.val);
+        let mut res = self.chain_with(val, dval, a, da);
+        res.combine(F::one(), b, db);
+        res
+    }
+}
+
+impl<F: Scalar> ops::AddAssign<&SparseDual<F>> for SparseDual<F> {
+    fn add_assign(&mut self, rhs: &SparseDual<F>) {
+        self.val += rhs.val;
+        self.combine(F::one(), rhs, F::one());
+    }
+}
+
+impl<F: Scalar> ops::AddAssign<F> for SparseDual<F> {
+    fn add_assign(&mut self, rhs: F) {
+        self.val += rhs;
+    }
+}
+
+impl<F: Scalar> ops::SubAssign<&SparseDual<F>> for SparseDual<F> {
+    fn sub_assign(&mut self, rhs: &SparseDual<F>) {
+        self.val -= rhs.val;
+        self.combine(F::one(), rhs, -F::one());
+    }
+}
+
+impl<F: Scalar> ops::SubAssign<F> for SparseDual<F> {
+    fn sub_assign(&mut self, rhs: F) {
+        self.val -= rhs;
+    }
+}
+
+impl<F: Scalar> ops::MulAssign<&SparseDual<F>> for SparseDual<F> {
+    fn mul_assign(&mut self, rhs: &SparseDual<F>) {
+        let (vs, vr) = (self.val, rhs.val);
+        self.val *= vr;
+        self.combine(vr, rhs, vs);
+    }
+}
+
+impl<F: Scalar> ops::MulAssign<F> for SparseDual<F> {
+    fn mul_assign(&mut self, rhs: F) {
+        self.val *= rhs;
+        for d in self.diffs.iter_mut() {
+            *d *= rhs;
+        }
+    }
+}
+
+impl<F: Scalar> ops::DivAssign<&SparseDual<F>> for SparseDual<F> {
+    fn div_assign(&mut self, rhs: &SparseDual<F>) {
+        let r = rhs.val.recip();
+        let q = self.val * r;
+        self.val = q;
+        self.combine(r, rhs, -q * r);
+    }
+}
+
+impl<F: Scalar> ops::DivAssign<F> for SparseDual<F> {
+    fn div_assign(&mut self, rhs: F) {
+        self.val /= rhs;
+        for d in self.diffs.iter_mut() {
+            *d /= rhs;
+        }
+    }
+}
+
+derive_ops_for!(SparseDual<F>: Add, AddAssign, add, add_assign);
+derive_ops_for!(SparseDual<F>: Sub, SubAssign, sub, sub_assign);
+derive_ops_for!(SparseDual<F>: Mul, MulAssign, mul, mul_assign);
+derive_ops_for!(SparseDual<F>: Div, DivAssign, div, div_assign);
+scalar_lhs_ops_for!(SparseDual<F>: f32);
+scalar_lhs_ops_for!(SparseDual<F>: f64);
+
+impl<F: Scalar> ops::Neg for SparseDual<F> {
+    type Output = Self;
+    fn neg(mut self) -> Self {
+        self.val = -self.val;
+        for d in self.diffs.iter_mut() {
+            *d = -*d;
+        }
+        self
+    }
+}
+
+#[cfg(feature = "implicit-clone")]
+impl<F: Scalar> ops::Neg for &SparseDual<F> {
+    type Output = SparseDual<F>;
+    fn neg(self) -> SparseDual<F> {
+        -self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Owning = Dual<Vec<f64>, RW, f64>;
+
+    // x0 = 0.5 + e0, x1 = 1.5 + e3, x2 = 0.25 + e1 + 2 e3
+    fn sparse() -> [SparseDual<f64>; 3] {
+        [
+            SparseDual::variable(0.5, 0),
+            SparseDual::variable(1.5, 3),
+            SparseDual::from_pairs(0.25, vec![(3, 1.), (1, 1.), (3, 1.)]),
+        ]
+    }
+
+    fn dense() -> [Owning; 3] {
+        [
+            Owning::from(vec![0.5, 1., 0., 0., 0.]),
+            Owning::from(vec![1.5, 0., 0., 0., 1.]),
+            Owning::from(vec![0.25, 0., 1., 0., 2.]),
+        ]
+    }
+
+    macro_rules! check_against_dense {
+        ($s : expr, $d : expr) => {
+            let (s, d) = ($s, $d);
+            assert!(s.to_dual(4).is_close(&d, 1e-12), "{:?} =/= {:?}", s, d);
+        };
+    }
+
+    #[test]
+    fn test_construction() {
+        let [_, _, x2] = sparse();
+        assert_eq!(x2.indices(), &[1, 3]);
+        assert_eq!(x2.diffs(), &[1., 2.]);
+        assert_eq!(x2.nnz(), 2);
+        assert_eq!(x2.diff(3), 2.);
+        assert_eq!(x2.diff(2), 0.);
+        assert_eq!(x2.iter().collect::<Vec<_>>(), vec![(1, 1.), (3, 2.)]);
+        assert_eq!(SparseDual::from_dual(&dense()[2]), x2);
+        assert_eq!(SparseDual::constant(2.).to_dual(2), Owning::constant(2., 2));
+    }
+
+    #[test]
+    fn test_ops() {
+        let [x0, x1, x2] = sparse();
+        let [d0, d1, d2] = dense();
+        check_against_dense!(x0.clone() + &x1, d0.clone() + d1.view());
+        check_against_dense!(x1.clone() - &x2, d1.clone() - d2.view());
+        check_against_dense!(x0.clone() * &x2, d0.clone() * d2.view());
+        check_against_dense!(x2.clone() / &x1, d2.clone() / d1.view());
+        check_against_dense!(x1.clone() * x2.clone(), d1.clone() * d2.view());
+        check_against_dense!(-x2.clone() + 2., -d2.clone() + 2.);
+        check_against_dense!(x0.clone() * 3. - 1., d0.clone() * 3. - 1.);
+        check_against_dense!(x0.clone() / 4., d0.clone() / 4.);
+        check_against_dense!(2. - x2.clone(), 2. - d2.clone());
+        check_against_dense!(2. / x2.clone(), d2.clone().inv() * 2.);
+        check_against_dense!(2. * x1.clone() + x0.clone(), 2. * d1.clone() + d0.view());
+        let mut acc = SparseDual::constant(1.);
+        acc *= &x0;
+        acc += x2.clone();
+        acc /= x1.clone();
+        acc -= 1.;
+        check_against_dense!(acc, (d0.clone() + d2.view()) / d1.view() - 1.);
+        // The index sets are merged, even where the result is null.
+        assert_eq!((x2.clone() - &x1).indices(), &[1, 3]);
+    }
+
+    #[test]
+    fn test_functions() {
+        let [x0, x1, x2] = sparse();
+        let [d0, d1, d2] = dense();
+        check_against_dense!(x0.clone().exp(), d0.clone().exp());
+        check_against_dense!(x0.clone().exp2(), d0.clone().exp2());
+        check_against_dense!(x2.clone().ln(), d2.clone().ln());
+        check_against_dense!(x2.clone().log2(), d2.clone().log2());
+        check_against_dense!(x2.clone().log10(), d2.clone().log10());
+        check_against_dense!(x2.clone().inv(), d2.clone().inv());
+        check_against_dense!(x2.clone().powf(2.5), d2.clone().powf(2.5));
+        let z = SparseDual::variable(0., 2).powf(0.5);
+        assert_eq!((z.val(), z.diff(2)), (0., f64::INFINITY));
+        check_against_dense!(x2.clone().powi(3), d2.clone().powi(3));
+        check_against_dense!(x2.clone().powi(0), d2.clone().powi(0));
+        check_against_dense!(x2.clone().sqrt(), d2.clone().sqrt());
+        check_against_dense!(x2.clone().cbrt(), d2.clone().cbrt());
+        check_against_dense!((-x2.clone()).abs(), (-d2.clone()).abs());
+        check_against_dense!(x0.clone().sin(), d0.clone().sin());
+        check_against_dense!(x0.clone().cos(), d0.clone().cos());
+        check_against_dense!(x0.clone().tan(), d0.clone().tan());
+        check_against_dense!(x0.clone().asin(), d0.clone().asin());
+        check_against_dense!(x0.clone().acos(), d0.clone().acos());
+        check_against_dense!(x0.clone().atan(), d0.clone().atan());
+        check_against_dense!(x0.clone().sinh(), d0.clone().sinh());
+        check_against_dense!(x0.clone().cosh(), d0.clone().cosh());
+        check_against_dense!(x0.clone().tanh(), d0.clone().tanh());
+        check_against_dense!(x0.clone().asinh(), d0.clone().asinh());
+        check_against_dense!(x1.clone().acosh(), d1.clone().acosh());
+        check_against_dense!(x0.clone().atanh(), d0.clone().atanh());
+        check_against_dense!(x2.clone().ln_1p(), d2.clone().ln_1p());
+        check_against_dense!(x0.clone().exp_m1(), d0.clone().exp_m1());
+        check_against_dense!(x2.clone().recip_sqrt(), d2.clone().recip_sqrt());
+        check_against_dense!(x2.clone().log(3.), d2.clone().log(3.));
+    }
+
+    #[test]
+    fn test_binary_functions() {
+        let [x0, x1, x2] = sparse();
+        let [d0, d1, d2] = dense();
+        check_against_dense!(x0.clone().hypot(&x2), d0.clone().hypot(d2.view()));
+        check_against_dense!(x0.clone().atan2(&x1), d0.clone().atan2(d1.view()));
+        check_against_dense!(x1.clone().powdual(&x2), d1.clone().powdual(d2.view()));
+        check_against_dense!(x0.clone().mul_add(&x1, &x2), d0.clone().mul_add(&d1, &d2));
+        let origin = SparseDual::variable(0., 1).hypot(&SparseDual::variable(0., 2));
+        assert_eq!(origin.diffs(), &[0., 0.]);
+    }
+
+    #[test]
+    fn test_special_functions() {
+        let [x0, x1, x2] = sparse();
+        let [d0, d1, d2] = dense();
+        check_against_dense!(x1.clone().gamma(), d1.clone().gamma());
+        check_against_dense!(x1.clone().ln_gamma(), d1.clone().ln_gamma());
+        check_against_dense!(x1.clone().digamma(), d1.clone().digamma());
+        check_against_dense!(x0.clone().erf(), d0.clone().erf());
+        check_against_dense!(x0.clone().erfc(), d0.clone().erfc());
+        check_against_dense!(x0.clone().normal_cdf(), d0.clone().normal_cdf());
+        check_against_dense!(x2.clone().probit(), d2.clone().probit());
+        check_against_dense!(x1.clone().bessel_j(1), d1.clone().bessel_j(1));
+        check_against_dense!(x1.clone().bessel_y(0), d1.clone().bessel_y(0));
+        check_against_dense!(x1.clone().bessel_i(0.5), d1.clone().bessel_i(0.5));
+        check_against_dense!(x1.clone().bessel_k(0.5), d1.clone().bessel_k(0.5));
+        check_against_dense!(
+            x1.clone().bessel_i_dual(&x2),
+            d1.clone().bessel_i_dual(d2.view())
+        );
+        check_against_dense!(
+            x1.clone().bessel_k_dual(&x2),
+            d1.clone().bessel_k_dual(d2.view())
+        );
+        check_against_dense!(x1.clone().gamma_p(&x2), d1.clone().gamma_p(d2.view()));
+        check_against_dense!(x1.clone().gamma_q(&x2), d1.clone().gamma_q(d2.view()));
+        check_against_dense!(
+            x0.clone().beta_inc(&x1, &x2),
+            d0.clone().beta_inc(d1.view(), d2.view())
+        );
+    }
+}
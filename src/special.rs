@@ -0,0 +1,91 @@
+//! Special functions over `Dual`s.
+//!
+//! The scalar implementations are written generically over `Scalar`,
+//! so that they work for `f32`, `f64` or any other scalar type.
+
+use crate::{CompatibleWith, Dual, OwningMode, ROAble, RWAble, Scalar, RW};
+
+pub(crate) mod bessel;
+pub(crate) mod erf;
+pub(crate) mod gamma;
+
+/// Number of iterations after which series and continued fractions are cut.
+const MAX_ITER: usize = 1000;
+
+/// Converts a `f64` literal to the scalar type `F`.
+fn cst<F: Scalar>(x: f64) -> F {
+    F::from(x).unwrap()
+}
+
+/// Whether `a` and `b` have the same value.
+///
+/// Unlike `==`, it ignores the derivatives of nested duals, so that special points are recognized
+/// whatever the direction in which they are differentiated.
+fn same<F: Scalar>(a: F, b: F) -> bool {
+    a.partial_cmp(&b) == Some(core::cmp::Ordering::Equal)
+}
+
+/// Whether all the components of `term` are negligible with respect to those of `sum`.
+///
+/// Used as a stopping criterion for series computed over duals.
+fn negligible<T, F>(term: &Dual<T, RW, F>, sum: &Dual<T, RW, F>) -> bool
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    term.as_slice()
+        .iter()
+        .zip(sum.as_slice())
+        .all(|(t, s)| t.abs() <= s.abs() * F::epsilon())
+}
+
+/// Whether `del` is one up to the machine precision, and its differentials are null.
+///
+/// Used as a stopping criterion for continued fractions computed over duals.
+fn close_to_one<T, F>(del: &Dual<T, RW, F>) -> bool
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    (del.val() - F::one()).abs() <= F::epsilon()
+        && del.diffs().iter().all(|d| d.abs() <= F::epsilon())
+}
+
+/// Replaces a value too close to zero by a tiny one, to avoid dividing by zero
+/// in the modified Lentz method.
+fn avoid_zero<T, F>(d: &mut Dual<T, RW, F>)
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    let tiny = F::min_positive_value() / F::epsilon();
+    if d.val().abs() < tiny {
+        *d.val_mut() = tiny;
+    }
+}
+
+impl<T, F> Dual<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    /// Adds `factor` times the differentials of `other` to the differentials of self.
+    fn add_diffs<S, M2>(&mut self, other: &Dual<S, M2, F>, factor: F)
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        assert_eq!(
+            self.ndiffs(),
+            other.ndiffs(),
+            "Duals have different numbers of diffs: {} =/= {}.",
+            self.ndiffs(),
+            other.ndiffs()
+        );
+        self.diffs_mut()
+            .iter_mut()
+            .zip(other.diffs())
+            .for_each(|(ds, dof)| *ds += factor * *dof);
+    }
+}
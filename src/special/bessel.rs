@@ -0,0 +1,531 @@
+use super::gamma::{digamma, ln_gamma};
+use super::{cst, same, MAX_ITER};
+use crate::{CompatibleWith, Dual, OwningMode, ROAble, RWAble, Scalar, RW};
+use core::cmp::Ordering;
+
+/// Euler–Mascheroni constant.
+const EULER: f64 = 0.577_215_664_901_532_9;
+
+/// Step used for the trapezoidal quadrature of K_ν.
+const K_STEP: f64 = 0.05;
+
+/// Argument above which J and Y are computed with their Hankel asymptotic expansions.
+const HANKEL_MIN: f64 = 25.;
+
+/// Returns J_0(x), ..., J_m(x) for x > 0, with m > nmax, using Miller's backward recurrence
+/// normalized by J_0 + 2 (J_2 + J_4 + ...) = 1.
+///
+/// The last values of the sequence are inaccurate but negligible.
+fn bessel_j_seq<F: Scalar>(nmax: usize, x: F) -> Vec<F> {
+    let big = F::max_value().sqrt();
+    let start = nmax.max(x.to_usize().unwrap_or(0)) + 1;
+    let mut m = start + 20 + (40. * start as f64).sqrt() as usize;
+    m += m % 2;
+    let mut j = vec![F::zero(); m + 2];
+    j[m] = F::one();
+    for k in (1..=m).rev() {
+        // J_(k-1) = 2k/x J_k - J_(k+1)
+        j[k - 1] = F::from(2 * k).unwrap() / x * j[k] - j[k + 1];
+        if j[k - 1].abs() > big {
+            for v in &mut j[k - 1..] {
+                *v /= big;
+            }
+        }
+    }
+    let norm = j[0] + cst::<F>(2.) * j[2..].iter().step_by(2).fold(F::zero(), |s, v| s + *v);
+    j.truncate(m + 1);
+    for v in &mut j {
+        *v /= norm;
+    }
+    j
+}
+
+/// Returns J_0(x), J_1(x), Y_0(x) and Y_1(x) for x ≥ `HANKEL_MIN`, using Hankel's asymptotic expansion
+/// J_ν = √(2/(πx)) (P cos χ - Q sin χ) and Y_ν = √(2/(πx)) (P sin χ + Q cos χ), with χ = x - (ν/2 + 1/4)π.
+///
+/// The series P and Q are summed until their terms stop decreasing.
+fn bessel_jy01_asymptotic<F: Scalar>(x: F) -> (F, F, F, F) {
+    let (sin, cos) = x.sin_cos();
+    let amp = (F::FRAC_2_PI() / x).sqrt();
+    let mut res = [F::zero(); 4];
+    for nu in 0..2 {
+        let mu = F::from(4 * nu * nu).unwrap();
+        let mut p = F::one();
+        let mut q = F::zero();
+        let mut term = F::one();
+        for k in 1..MAX_ITER {
+            let odd = F::from(2 * k - 1).unwrap();
+            let next = term * (mu - odd * odd) / (F::from(8 * k).unwrap() * x);
+            if next.abs() >= term.abs() {
+                break;
+            }
+            term = next;
+            // The signs of the terms alternate within P and within Q
+            let t = if k % 4 < 2 { term } else { -term };
+            if k % 2 == 0 {
+                p += t;
+            } else {
+                q += t;
+            }
+            if term.abs() <= F::epsilon() {
+                break;
+            }
+        }
+        // cos χ and sin χ are expanded so that x is reduced by the accurate sin_cos
+        let (cchi, schi) = if nu == 0 {
+            (cos + sin, sin - cos)
+        } else {
+            (sin - cos, -sin - cos)
+        };
+        let (cchi, schi) = (cchi * F::FRAC_1_SQRT_2(), schi * F::FRAC_1_SQRT_2());
+        res[nu] = amp * (p * cchi - q * schi);
+        res[2 + nu] = amp * (p * schi + q * cchi);
+    }
+    (res[0], res[1], res[2], res[3])
+}
+
+/// Returns J_n(x) and its derivative with respect to x.
+pub(crate) fn bessel_j<F: Scalar>(n: i32, x: F) -> (F, F) {
+    let na = n.unsigned_abs() as usize;
+    // J_-n = (-1)^n J_n and J_n(-x) = (-1)^n J_n(x)
+    let odd = na % 2 == 1;
+    let sn = if n < 0 && odd { -F::one() } else { F::one() };
+    let (sv, sd) = match (x < F::zero(), odd) {
+        (true, true) => (-sn, sn),
+        (true, false) => (sn, -sn),
+        (false, _) => (sn, sn),
+    };
+    let xa = x.abs();
+    if xa.partial_cmp(&F::zero()).is_none() {
+        return (x, x);
+    }
+    if xa > F::max_value() {
+        return (F::zero(), F::zero());
+    }
+    let j = if same(xa, F::zero()) {
+        let mut j = vec![F::zero(); na + 2];
+        j[0] = F::one();
+        j
+    } else if xa >= cst::<F>(HANKEL_MIN) && xa > F::from(na + 1).unwrap() {
+        // The forward recurrence J_(k+1) = 2k/x J_k - J_(k-1) is stable for k < x
+        let (j0, j1, _, _) = bessel_jy01_asymptotic(xa);
+        let mut j = vec![j0, j1];
+        for k in 1..=na {
+            let next = F::from(2 * k).unwrap() / xa * j[k] - j[k - 1];
+            j.push(next);
+        }
+        j
+    } else {
+        bessel_j_seq(na + 1, xa)
+    };
+    // J_n' = (J_(n-1) - J_(n+1)) / 2
+    let jm = if na == 0 { -j[1] } else { j[na - 1] };
+    (sv * j[na], sd * (jm - j[na + 1]) / cst::<F>(2.))
+}
+
+/// Returns Y_0(x) and Y_1(x) for x > 0.
+///
+/// Y_0 is computed with its Neumann series over the J_2k, and Y_1 = -Y_0' by differentiating it.
+/// For x ≥ `HANKEL_MIN`, the asymptotic expansion is used instead.
+fn bessel_y01<F: Scalar>(x: F) -> (F, F) {
+    if x >= cst::<F>(HANKEL_MIN) {
+        let (_, _, y0, y1) = bessel_jy01_asymptotic(x);
+        return (y0, y1);
+    }
+    let j = bessel_j_seq(1, x);
+    let l = (x / cst::<F>(2.)).ln() + cst::<F>(EULER);
+    let mut s = F::zero();
+    let mut ds = F::zero();
+    let mut sign = -F::one();
+    for k in 1..(j.len() - 1) / 2 {
+        let kf = F::from(k).unwrap();
+        s += sign * j[2 * k] / kf;
+        ds += sign * (j[2 * k - 1] - j[2 * k + 1]) / (kf + kf);
+        sign = -sign;
+    }
+    let y0 = F::FRAC_2_PI() * (l * j[0] - s - s);
+    let dy0 = F::FRAC_2_PI() * (j[0] / x - l * j[1] - ds - ds);
+    (y0, -dy0)
+}
+
+/// Returns Y_n(x) and its derivative with respect to x.
+pub(crate) fn bessel_y<F: Scalar>(n: i32, x: F) -> (F, F) {
+    let na = n.unsigned_abs() as usize;
+    // Y_-n = (-1)^n Y_n
+    let sn = if n < 0 && na % 2 == 1 {
+        -F::one()
+    } else {
+        F::one()
+    };
+    if same(x, F::zero()) {
+        let inf = F::one() / F::zero();
+        return (-sn * inf, sn * inf);
+    }
+    // x is negative or NaN
+    if x.partial_cmp(&F::zero()) != Some(Ordering::Greater) {
+        let nan = F::zero() / F::zero();
+        return (nan, nan);
+    }
+    if x > F::max_value() {
+        return (F::zero(), F::zero());
+    }
+    let (y0, y1) = bessel_y01(x);
+    let mut y = vec![y0, y1];
+    // Forward recurrence, which is stable for Y: Y_(k+1) = 2k/x Y_k - Y_(k-1)
+    for k in 1..=na {
+        let next = F::from(2 * k).unwrap() / x * y[k] - y[k - 1];
+        y.push(next);
+    }
+    let ym = if na == 0 { -y[1] } else { y[na - 1] };
+    (sn * y[na], sn * (ym - y[na + 1]) / cst::<F>(2.))
+}
+
+/// Returns I_ν(x) and its derivatives with respect to x and ν, for x ≥ 0.
+///
+/// Negative orders are reduced to positive ones with the reflection formula
+/// I_-ν = I_ν + 2/π sin(νπ) K_ν.
+pub(crate) fn bessel_i<F: Scalar>(nu: F, x: F) -> (F, F, F) {
+    if nu.partial_cmp(&F::zero()) != Some(Ordering::Less) {
+        return bessel_i_pos(nu, x);
+    }
+    let m = -nu;
+    let (i, di, di_nu) = bessel_i_pos(m, x);
+    // sin(mπ) does not round to zero at integer orders, so the vanishing K_m term is dropped
+    if same(m.fract(), F::zero()) {
+        let (k, _, _) = bessel_k(m, x);
+        let sign = if same((m / cst::<F>(2.)).fract(), F::zero()) {
+            F::one()
+        } else {
+            -F::one()
+        };
+        // d/dm (2/π sin(mπ) K_m) = 2 cos(mπ) K_m at integer orders
+        return (i, di, -(di_nu + cst::<F>(2.) * sign * k));
+    }
+    let (k, dk, dk_nu) = bessel_k(m, x);
+    let (sin, cos) = (m * F::PI()).sin_cos();
+    let c = F::FRAC_2_PI() * sin;
+    (
+        i + c * k,
+        di + c * dk,
+        -(di_nu + cst::<F>(2.) * cos * k + c * dk_nu),
+    )
+}
+
+/// Returns I_ν(x) and its derivatives with respect to x and ν, for ν ≥ 0 and x ≥ 0.
+///
+/// The ascending series Σ (x/2)^(2k+ν) / (k! Γ(k+ν+1)), whose terms are all positive, is used.
+fn bessel_i_pos<F: Scalar>(nu: F, x: F) -> (F, F, F) {
+    if !(nu >= F::zero() && x >= F::zero()) {
+        let nan = F::zero() / F::zero();
+        return (nan, nan, nan);
+    }
+    if same(x, F::zero()) {
+        let half = cst::<F>(0.5);
+        let (i, di) = if same(nu, F::zero()) {
+            (F::one(), F::zero())
+        } else if same(nu, F::one()) {
+            // I_1(x) ~ x/2, which keeps the derivatives of x when it is itself a dual
+            (x * half, half)
+        } else if nu > F::one() {
+            (F::zero(), F::zero())
+        } else {
+            (F::zero(), F::one() / F::zero())
+        };
+        return (i, di, F::zero());
+    }
+    let hx = x / cst::<F>(2.);
+    let lhx = hx.ln();
+    let q = hx * hx;
+    let mut term = (nu * lhx - ln_gamma(nu + F::one())).exp();
+    let mut psi = digamma(nu + F::one());
+    let mut sum = F::zero();
+    let mut sum_next = F::zero();
+    let mut sum_nu = F::zero();
+    for k in 0..MAX_ITER {
+        let kf = F::from(k).unwrap();
+        // Term of I_(ν+1), used for the derivative with respect to x
+        let term_next = term * hx / (kf + nu + F::one());
+        let term_nu = term * (lhx - psi);
+        sum += term;
+        sum_next += term_next;
+        sum_nu += term_nu;
+        if term <= sum * F::epsilon()
+            && term_next <= sum_next * F::epsilon()
+            && term_nu.abs() <= sum_nu.abs() * F::epsilon()
+        {
+            break;
+        }
+        term *= q / ((kf + F::one()) * (kf + nu + F::one()));
+        psi += (kf + nu + F::one()).recip();
+    }
+    // I_ν' = I_(ν+1) + ν/x I_ν
+    (sum, sum_next + nu / x * sum, sum_nu)
+}
+
+/// Returns K_ν(x) and its derivatives with respect to x and ν, for x ≥ 0.
+///
+/// The integral K_ν(x) = ∫ exp(-x cosh t) cosh(νt) dt over [0, ∞) is computed with
+/// the trapezoidal rule, which converges exponentially fast for such integrands.
+/// The derivatives are obtained by differentiating under the integral sign.
+/// If the integrand is still not negligible after `MAX_ITER * 10` steps, as for tiny x, NaNs are returned.
+pub(crate) fn bessel_k<F: Scalar>(nu: F, x: F) -> (F, F, F) {
+    if same(x, F::zero()) {
+        let inf = F::one() / F::zero();
+        // K_ν = K_-ν, so that the derivative with respect to ν vanishes at ν = 0
+        let dnu = if same(nu, F::zero()) {
+            F::zero()
+        } else {
+            nu.signum() * inf
+        };
+        return (inf, -inf, dnu);
+    }
+    // x is negative or NaN
+    if x.partial_cmp(&F::zero()) != Some(Ordering::Greater) {
+        let nan = F::zero() / F::zero();
+        return (nan, nan, nan);
+    }
+    let h = cst::<F>(K_STEP);
+    // The integrand is increasing before this point
+    let peak = (nu / x).abs().asinh();
+    let mut sum = F::zero();
+    let mut sum_x = F::zero();
+    let mut sum_nu = F::zero();
+    for k in 0..(MAX_ITER * 10) {
+        let t = F::from(k).unwrap() * h;
+        let cosh = t.cosh();
+        let ep = (nu * t - x * cosh).exp();
+        let em = (-nu * t - x * cosh).exp();
+        let w = if k == 0 { cst::<F>(0.5) } else { F::one() };
+        let f = w * (ep + em) / cst::<F>(2.);
+        let f_nu = w * t * (ep - em) / cst::<F>(2.);
+        sum += f;
+        sum_x -= f * cosh;
+        sum_nu += f_nu;
+        if t > peak
+            && f <= sum * F::epsilon()
+            && f * cosh <= -sum_x * F::epsilon()
+            && f_nu.abs() <= sum_nu.abs() * F::epsilon()
+        {
+            return (sum * h, sum_x * h, sum_nu * h);
+        }
+    }
+    // The integral was truncated before the integrand became negligible
+    let nan = F::zero() / F::zero();
+    (nan, nan, nan)
+}
+
+/// Bessel functions
+impl<T, F> Dual<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    /// Returns J_n(self), the Bessel function of the first kind of integer order `n`.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// // J_0' = -J_1
+    /// let x = Dual::<_, RW, f64>::from([2.5, 1.]);
+    /// let j0 = x.bessel_j(0);
+    /// let j1 = x.bessel_j(1);
+    /// assert!((j0.diffs()[0] + j1.val()).abs() < 1e-14);
+    /// ```
+    pub fn bessel_j(self, n: i32) -> Self {
+        let (j, dj) = bessel_j(n, self.val());
+        self.chain(j, dj)
+    }
+
+    /// Returns Y_n(self), the Bessel function of the second kind of integer order `n`.
+    ///
+    /// Y_n is only defined for self ≥ 0, outside of which the value and differentials are NaN.
+    pub fn bessel_y(self, n: i32) -> Self {
+        let (y, dy) = bessel_y(n, self.val());
+        self.chain(y, dy)
+    }
+
+    /// Returns I_ν(self), the modified Bessel function of the first kind of real order `nu`.
+    ///
+    /// I_ν is only computed for self ≥ 0, outside of which the value and differentials are NaN.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// // I_-1/2(x) = √(2/(πx)) cosh(x)
+    /// let x = Dual::<_, RW, f64>::from([1.5, 1.]);
+    /// let expected = (x.clone() * std::f64::consts::FRAC_PI_2).inv().sqrt() * x.clone().cosh();
+    /// assert!(x.bessel_i(-0.5).is_close(&expected, 1e-13));
+    /// ```
+    pub fn bessel_i(self, nu: F) -> Self {
+        let (i, di, _) = bessel_i(nu, self.val());
+        self.chain(i, di)
+    }
+
+    /// Returns I_ν(self), the modified Bessel function of the first kind,
+    /// where the order ν is itself a dual.
+    ///
+    /// The differentials account for both self and the order `nu`.
+    /// I_ν is only computed for self ≥ 0, outside of which the value and differentials are NaN.
+    pub fn bessel_i_dual<S, M2>(self, nu: Dual<S, M2, F>) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        let (i, di, di_nu) = bessel_i(nu.val(), self.val());
+        let mut res = self.chain(i, di);
+        res.add_diffs(&nu, di_nu);
+        res
+    }
+
+    /// Returns K_ν(self), the modified Bessel function of the second kind of real order `nu`.
+    ///
+    /// K_ν is only defined for self ≥ 0, outside of which the value and differentials are NaN.
+    pub fn bessel_k(self, nu: F) -> Self {
+        let (k, dk, _) = bessel_k(nu, self.val());
+        self.chain(k, dk)
+    }
+
+    /// Returns K_ν(self), the modified Bessel function of the second kind,
+    /// where the order ν is itself a dual.
+    ///
+    /// The differentials account for both self and the order `nu`.
+    /// K_ν is only defined for self ≥ 0, outside of which the value and differentials are NaN.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// // K_ν = K_-ν, so that its derivative with respect to ν vanishes at ν = 0.
+    /// let x = Dual::<_, RW, f64>::from([1.5, 0.]);
+    /// let nu = Dual::<_, RW, f64>::from([0., 1.]);
+    /// assert!(x.bessel_k_dual(nu).diffs()[0].abs() < 1e-14);
+    /// ```
+    pub fn bessel_k_dual<S, M2>(self, nu: Dual<S, M2, F>) -> Self
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        let (k, dk, dk_nu) = bessel_k(nu.val(), self.val());
+        let mut res = self.chain(k, dk);
+        res.add_diffs(&nu, dk_nu);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instanciations::vecf64::Owning;
+
+    fn assert_rel(a: f64, b: f64, what: &str) {
+        assert!(
+            (a - b).abs() <= 1e-13 * b.abs(),
+            "{}: {} =/= {}",
+            what,
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_jy_values() {
+        // Reference values computed with mpmath
+        let refs: [(i32, f64, f64, f64); 4] = [
+            (0, 1., 0.7651976865579666, 0.08825696421567696),
+            (1, 2.5, 0.49709410246427405, 0.1459181379667858),
+            (5, 12.7, 0.07603947775035458, -0.22054627174439556),
+            (-3, 0.3, -0.000559343047748846, 190.77481501430938),
+        ];
+        for &(n, x, j, y) in refs.iter() {
+            assert_rel(bessel_j(n, x).0, j, "J");
+            assert_rel(bessel_y(n, x).0, y, "Y");
+        }
+        assert_eq!(bessel_j(2, -1.).0, bessel_j(2, 1.).0);
+        assert_eq!(bessel_j(3, -1.).0, -bessel_j(3, 1.).0);
+    }
+
+    #[test]
+    fn test_ik_values() {
+        // Reference values computed with mpmath
+        let refs: [(f64, f64, f64, f64); 4] = [
+            (0., 1., 1.2660658777520084, 0.42102443824070834),
+            (0.5, 2.5, 3.0530935381967184, 0.06506594315400999),
+            (2.3, 12.7, 29851.39302171296, 1.2985558038938794e-06),
+            (7.5, 0.3, 4.727590048391661e-11, 1409014685.6587043),
+        ];
+        for &(nu, x, i, k) in refs.iter() {
+            assert_rel(bessel_i(nu, x).0, i, "I");
+            assert_rel(bessel_k(nu, x).0, k, "K");
+        }
+    }
+
+    #[test]
+    fn test_jy_large_values() {
+        // Reference values computed with mpmath
+        let refs: [(i32, f64, f64, f64); 5] = [
+            (0, 26., 0.15599931552242113, 0.012044625860755603),
+            (1, 40.5, 0.11269052994059431, 0.05498584250054305),
+            (4, 30., -0.05260900032132035, -0.1365312411147536),
+            (3, 1e5, -0.0018466887933605122, -0.0017192842193592435),
+            (0, 1e12, 1.016712505004068e-07, -7.913802683850949e-07),
+        ];
+        for &(n, x, j, y) in refs.iter() {
+            assert_rel(bessel_j(n, x).0, j, "J");
+            assert_rel(bessel_y(n, x).0, y, "Y");
+        }
+        assert_eq!(bessel_j(2, f64::INFINITY), (0., 0.));
+        assert_eq!(bessel_j(3, f64::NEG_INFINITY), (0., 0.));
+        assert_eq!(bessel_y(1, f64::INFINITY), (0., 0.));
+        assert!(bessel_j(0, f64::NAN).0.is_nan());
+    }
+
+    #[test]
+    fn test_i_negative_order() {
+        // Reference values computed with mpmath
+        let refs: [(f64, f64, f64, f64); 3] = [
+            (-1.3, 2.1, 1.3584098106686238, 1.2407621777872018),
+            (-2.5, 0.7, 5.425153459061945, -9.669120281482824),
+            (-3., 1.2, 0.03935900306489002, 7.891786338518619),
+        ];
+        for &(nu, x, i, di_nu) in refs.iter() {
+            let res = bessel_i(nu, x);
+            assert_rel(res.0, i, "I");
+            assert!((res.2 - di_nu).abs() < 1e-10 * di_nu.abs(), "dI/dnu");
+            let h = 1e-6;
+            let di_x = (bessel_i(nu, x + h).0 - bessel_i(nu, x - h).0) / (2. * h);
+            assert!((res.1 - di_x).abs() < 1e-8 * di_x.abs(), "dI/dx");
+        }
+    }
+
+    #[test]
+    fn test_dual_bessel() {
+        let x = Owning::from(vec![3.2, 1., 0.]);
+        let nu = Owning::from(vec![1.7, 0., 1.]);
+        let h = 1e-6;
+        let i = x.clone().bessel_i_dual(nu.view());
+        assert_eq!(i.val(), x.clone().bessel_i(1.7).val());
+        let di_nu = (bessel_i(1.7 + h, 3.2).0 - bessel_i(1.7 - h, 3.2).0) / (2. * h);
+        let di_x = (bessel_i(1.7, 3.2 + h).0 - bessel_i(1.7, 3.2 - h).0) / (2. * h);
+        assert!(i.is_close(&Owning::from(vec![i.val(), di_x, di_nu]), 1e-8));
+        let k = x.clone().bessel_k_dual(nu);
+        let dk_nu = (bessel_k(1.7 + h, 3.2).0 - bessel_k(1.7 - h, 3.2).0) / (2. * h);
+        let dk_x = (bessel_k(1.7, 3.2 + h).0 - bessel_k(1.7, 3.2 - h).0) / (2. * h);
+        assert!(k.is_close(&Owning::from(vec![k.val(), dk_x, dk_nu]), 1e-8));
+        // Wronskian: J_1 Y_0 - J_0 Y_1 = 2/(πx)
+        let w = x.clone().bessel_j(1) * x.clone().bessel_y(0)
+            - x.clone().bessel_j(0) * x.clone().bessel_y(1);
+        let expected = (x * std::f64::consts::PI).inv() * 2.;
+        assert!(w.is_close(&expected, 1e-12));
+    }
+
+    #[test]
+    fn test_k_at_zero() {
+        let inf = f64::INFINITY;
+        assert_eq!(bessel_k(0., 0.), (inf, -inf, 0.));
+        assert_eq!(bessel_k(-1.5, 0.), (inf, -inf, -inf));
+    }
+
+    #[test]
+    fn test_k_truncated() {
+        let (k, dk, dk_nu) = bessel_k(0.5_f64, 1e-300);
+        assert!(k.is_nan() && dk.is_nan() && dk_nu.is_nan());
+    }
+}
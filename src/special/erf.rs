@@ -0,0 +1,207 @@
+use super::{cst, same, MAX_ITER};
+use crate::{rules, Dual, RWAble, Scalar, RW};
+
+/// Error function, computed by its series expansion for small arguments
+/// and through `erfc` otherwise.
+pub(crate) fn erf<F: Scalar>(x: F) -> F {
+    if x.abs() >= cst::<F>(2.) {
+        return x.signum() * (F::one() - erfc_cf(x.abs()));
+    }
+    // erf(x) = 2/sqrt(pi) exp(-x^2) sum_n (2x^2)^n x / (1*3*...*(2n+1))
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    for n in 1..MAX_ITER {
+        term *= (x2 + x2) / F::from(2 * n + 1).unwrap();
+        sum += term;
+        if term.abs() <= sum.abs() * F::epsilon() {
+            break;
+        }
+    }
+    F::FRAC_2_SQRT_PI() * (-x2).exp() * sum
+}
+
+/// Complementary error function, `1 - erf(x)`, without the loss of precision for large `x`.
+pub(crate) fn erfc<F: Scalar>(x: F) -> F {
+    if x < cst(0.5) {
+        F::one() - erf(x)
+    } else {
+        erfc_cf(x)
+    }
+}
+
+/// Continued fraction for erfc, valid for x > 0, evaluated with the modified Lentz method:
+/// erfc(x) = exp(-x^2)/sqrt(pi) / (x + (1/2)/(x + 1/(x + (3/2)/(x + ...))))
+fn erfc_cf<F: Scalar>(x: F) -> F {
+    let tiny = F::min_positive_value() / F::epsilon();
+    let mut f = x;
+    let mut c = x;
+    let mut d = F::zero();
+    for n in 1..MAX_ITER {
+        let a = F::from(n).unwrap() / cst::<F>(2.);
+        d = x + a * d;
+        if same(d, F::zero()) {
+            d = tiny;
+        }
+        c = x + a / c;
+        if same(c, F::zero()) {
+            c = tiny;
+        }
+        d = d.recip();
+        let delta = c * d;
+        f *= delta;
+        if (delta - F::one()).abs() <= F::epsilon() {
+            break;
+        }
+    }
+    (-x * x).exp() / F::PI().sqrt() / f
+}
+
+/// Cumulative distribution function of the standard normal distribution.
+pub(crate) fn normal_cdf<F: Scalar>(x: F) -> F {
+    erfc(-x * F::FRAC_1_SQRT_2()) / cst::<F>(2.)
+}
+
+/// Probability density function of the standard normal distribution.
+pub(crate) fn normal_pdf<F: Scalar>(x: F) -> F {
+    (-x * x / cst::<F>(2.)).exp() / (F::PI() + F::PI()).sqrt()
+}
+
+/// Quantile function of the standard normal distribution.
+///
+/// The initial guess (Abramowitz & Stegun 26.2.23) is refined using Halley's method.
+pub(crate) fn probit<F: Scalar>(p: F) -> F {
+    if !(p > F::zero() && p < F::one()) {
+        return if same(p, F::zero()) {
+            -F::one() / F::zero()
+        } else if same(p, F::one()) {
+            F::one() / F::zero()
+        } else {
+            F::zero() / F::zero()
+        };
+    }
+    let q = p.min(F::one() - p);
+    let t = (-(q.ln() + q.ln())).sqrt();
+    let num = cst::<F>(2.515517) + t * (cst::<F>(0.802853) + t * cst::<F>(0.010328));
+    let den =
+        F::one() + t * (cst::<F>(1.432788) + t * (cst::<F>(0.189269) + t * cst::<F>(0.001308)));
+    let mut x = num / den - t;
+    if p > cst(0.5) {
+        x = -x;
+    }
+    for _ in 0..MAX_ITER {
+        // Computing the error on the smallest tail keeps the precision far from the median
+        let e = if x < F::zero() {
+            normal_cdf(x) - p
+        } else {
+            (F::one() - p) - normal_cdf(-x)
+        };
+        let u = e / normal_pdf(x);
+        let step = u / (F::one() + x * u / cst::<F>(2.));
+        x -= step;
+        if step.abs() <= x.abs() * F::epsilon() {
+            break;
+        }
+    }
+    x
+}
+
+/// Error function family
+impl<T, F> Dual<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    /// Returns erf(self), the error function.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let x = Dual::<_, RW, f64>::from([0., 1.]).erf();
+    /// assert_eq!(x.val(), 0.);
+    /// assert_eq!(x.diffs(), &[std::f64::consts::FRAC_2_SQRT_PI]);
+    /// ```
+    pub fn erf(self) -> Self {
+        let (val, dval) = rules::erf(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns erfc(self) = 1 - erf(self), the complementary error function.
+    ///
+    /// It does not suffer from the loss of precision of computing `1 - erf(self)` for large values.
+    pub fn erfc(self) -> Self {
+        let (val, dval) = rules::erfc(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns Φ(self), the cumulative distribution function of the standard normal distribution.
+    pub fn normal_cdf(self) -> Self {
+        let (val, dval) = rules::normal_cdf(self.val());
+        self.chain(val, dval)
+    }
+
+    /// Returns Φ⁻¹(self), the quantile function of the standard normal distribution (probit).
+    ///
+    /// At 0 and 1 the value is respectively -∞ and +∞ and the derivative is unbounded:
+    /// non-null differentials become +∞ or -∞ (following their sign),
+    /// while null differentials stay null.
+    pub fn probit(self) -> Self {
+        let (val, dval) = rules::probit(self.val());
+        self.chain(val, dval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instanciations::vecf64::Owning;
+
+    #[test]
+    fn test_erf_values() {
+        // Reference values computed with Python's math module
+        let refs: [(f64, f64); 6] = [
+            (0.1, 0.1124629160182849),
+            (0.5, 0.5204998778130465),
+            (1., 0.8427007929497149),
+            (1.9, 0.9927904292352575),
+            (2., 0.9953222650189527),
+            (3.5, 0.9999992569016276),
+        ];
+        for &(x, e) in refs.iter() {
+            assert!((erf(x) - e).abs() < 1e-15, "erf({})", x);
+            assert!((erf(-x) + e).abs() < 1e-15, "erf(-{})", x);
+        }
+        let refs: [(f64, f64); 6] = [
+            (0.3, 0.6713732405408726),
+            (0.5, 0.4795001221869535),
+            (1.5, 0.033894853524689274),
+            (5., 1.5374597944280351e-12),
+            (10., 2.088487583762545e-45),
+            (-1., 1.842700792949715),
+        ];
+        for &(x, e) in refs.iter() {
+            assert!((erfc(x) - e).abs() < 1e-14 * e, "erfc({})", x);
+        }
+    }
+
+    #[test]
+    fn test_probit() {
+        for &p in [1e-300, 1e-10, 0.01, 0.3, 0.5, 0.75, 0.99, 1. - 1e-10].iter() {
+            let x: f64 = probit(p);
+            assert!((normal_cdf(x) - p).abs() <= 1e-12 * p, "probit({})", p);
+        }
+        assert_eq!(probit(0.), f64::NEG_INFINITY);
+        assert_eq!(probit(1.), f64::INFINITY);
+        assert!(probit(1.5_f64).is_nan());
+    }
+
+    #[test]
+    fn test_dual_erf() {
+        let x = Owning::from(vec![0.7, 1., -2.]);
+        let d = std::f64::consts::FRAC_2_SQRT_PI * (-0.49_f64).exp();
+        let expected = Owning::from(vec![erf(0.7), d, -2. * d]);
+        assert!(x.clone().erf().is_close(&expected, 1e-14));
+        assert!(x.clone().erfc().is_close(&(-expected + 1.), 1e-14));
+        let p = x.clone().normal_cdf();
+        assert!(p.probit().is_close(&x, 1e-12));
+    }
+}
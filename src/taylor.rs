@@ -0,0 +1,898 @@
+//! A module containing truncated Taylor series, used to compute univariate derivatives of arbitrary order.
+//!
+//! A `Taylor` holds the coefficients `c_0, ..., c_K` of the expansion of a function around a point,
+//! `c_k` being its `k`-th derivative divided by `k!`.
+//! Operations propagate these coefficients using the standard recurrences on power series,
+//! so that evaluating a function at the variable `x + t` gives all of its derivatives at `x` up to order K.
+//!
+//! ```
+//! # use fwd_ad::*;
+//! // The 5th derivative of exp(2x) at 0 is 2^5
+//! let x = Taylor::<Vec<f64>, RW, f64>::variable(0., 5);
+//! assert!(((x * 2.).exp().derivative(5) - 32.).abs() < 1e-12);
+//! ```
+
+use super::{CompatibleWith, OwningMode, ROAble, RWAble, Scalar, ToOwning, ToView, RO, RW};
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use std::ops;
+
+/// The struct implementing truncated Taylor series.
+///
+/// As `Dual`, it is parametrized by a container type `T`, an owning mode `M` and a scalar type `F`.
+/// The container holds the coefficients of the series, starting with the value, so that its length is the order plus one.
+///
+/// `constant` and `variable` methods are provided for RW `Taylor`s backed by a `Vec` or an array of any size.
+///
+/// Most functions use a temporary buffer of the series' length.
+#[derive(PartialEq, Debug, Clone, Copy, Hash, Default)]
+pub struct Taylor<T, M, F>
+where
+    M: OwningMode,
+    T: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    content: T,
+    om: M,
+    ph_f: PhantomData<F>,
+}
+
+impl<T, M, F> From<T> for Taylor<T, M, F>
+where
+    M: OwningMode,
+    T: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn from(x: T) -> Self {
+        Taylor {
+            content: x,
+            om: M::default(),
+            ph_f: PhantomData,
+        }
+    }
+}
+
+/// Series are ordered by their constant coefficient, their value.
+impl<T, M, F> PartialOrd for Taylor<T, M, F>
+where
+    M: OwningMode + PartialEq,
+    T: ROAble<F> + CompatibleWith<M, F> + PartialEq,
+    F: Scalar,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.val().partial_cmp(&other.val())
+    }
+}
+
+impl<F> Taylor<Vec<F>, RW, F>
+where
+    F: Scalar,
+{
+    /// Generates a Taylor series backed by a Vec<F> of order `order`,
+    /// with value `value` and other coefficients set to 0.
+    pub fn constant(value: F, order: usize) -> Self {
+        let mut res = Taylor::from(vec![F::zero(); order + 1]);
+        res.content[0] = value;
+        res
+    }
+
+    /// Generates the Taylor series of the variable at `value`, backed by a Vec<F> of order `order`.
+    pub fn variable(value: F, order: usize) -> Self {
+        let mut res = Self::constant(value, order);
+        if order > 0 {
+            res.content[1] = F::one();
+        }
+        res
+    }
+}
+
+impl<F, const N: usize> Taylor<[F; N], RW, F>
+where
+    F: Scalar,
+{
+    /// Generates a Taylor expansion backed by an array, of a constant `value` up to the order `order`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order + 1` is not the size of the array.
+    pub fn constant(value: F, order: usize) -> Self {
+        assert_eq!(order + 1, N);
+        let mut res = Taylor::from([F::zero(); N]);
+        res.content[0] = value;
+        res
+    }
+
+    /// Generates a Taylor expansion backed by an array, of the variable at `value` up to the order `order`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order + 1` is not the size of the array.
+    pub fn variable(value: F, order: usize) -> Self {
+        let mut res = Self::constant(value, order);
+        if let Some(c) = res.content.get_mut(1) {
+            *c = F::one();
+        }
+        res
+    }
+}
+
+/// Implementations for Taylor series that do not necessarily own their content.
+impl<T, M, F> Taylor<T, M, F>
+where
+    M: OwningMode,
+    T: ROAble<F>,
+    T: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    /// Clone the borrowed content, so that the resulting Taylor series
+    /// owns its content.
+    pub fn to_owning(&self) -> Taylor<T::Owning, RW, F>
+    where
+        T: ToOwning<F>,
+    {
+        Taylor::from(self.content.to_owning())
+    }
+
+    /// Returns the coefficients as a slice.
+    pub fn as_slice(&self) -> &[F] {
+        self.content.ro()
+    }
+
+    /// Returns the value, that is the coefficient of order 0.
+    pub fn val(&self) -> F {
+        self.as_slice()[0]
+    }
+
+    /// Returns the order of the series.
+    pub fn order(&self) -> usize {
+        self.as_slice().len() - 1
+    }
+
+    /// Returns the `k`-th derivative, that is the coefficient of order `k` multiplied by `k!`.
+    ///
+    /// ```
+    /// # use fwd_ad::*;
+    /// let t = Taylor::<_, RW, f64>::from([1., 2., 3., 4.]);
+    /// assert_eq!(t.derivative(3), 24.);
+    /// ```
+    pub fn derivative(&self, k: usize) -> F {
+        let mut res = self.as_slice()[k];
+        let mut i = F::one();
+        for _ in 1..=k {
+            res *= i;
+            i += F::one();
+        }
+        res
+    }
+
+    /// Allows comparing to Taylor series by checking whether they are elementwise within `atol` of each other.
+    pub fn is_close<S, M2>(&self, b: &Taylor<S, M2, F>, atol: F) -> bool
+    where
+        M2: OwningMode,
+        S: ROAble<F>,
+        S: CompatibleWith<M2, F>,
+    {
+        self.as_slice()
+            .iter()
+            .zip(b.as_slice())
+            .all(|(xs, xb)| (*xs - *xb).abs() <= atol)
+    }
+
+    /// Returns a non-owning Taylor series backed by the ViewType of self.
+    pub fn view<'a>(&'a self) -> Taylor<&'a T::ViewType, RO, F>
+    where
+        T: ToView<F>,
+        &'a T::ViewType: CompatibleWith<RO, F>,
+    {
+        Taylor::from(self.content.view())
+    }
+
+    /// Consumes the `Taylor` and return the container inside it.
+    pub fn into_container(self) -> T {
+        self.content
+    }
+}
+
+//
+//
+// Recurrences on power series
+// ===========================
+//
+//
+
+fn index<F: Scalar>(k: usize) -> F {
+    F::from(k).unwrap()
+}
+
+// out = a * b
+fn mul_series<F: Scalar>(a: &[F], b: &[F], out: &mut [F]) {
+    for k in 0..out.len() {
+        out[k] = (0..=k).fold(F::zero(), |acc, j| acc + a[j] * b[k - j]);
+    }
+}
+
+// out = a / b
+fn div_series<F: Scalar>(a: &[F], b: &[F], out: &mut [F]) {
+    let r = b[0].recip();
+    for k in 0..out.len() {
+        let s = (1..=k).fold(F::zero(), |acc, j| acc + b[j] * out[k - j]);
+        out[k] = (a[k] - s) * r;
+    }
+}
+
+// out = f(a), knowing out[0] = f(a[0]) and h = f'(a), as f(a)' = h a'
+fn integrate_series<F: Scalar>(a: &[F], h: &[F], f0: F, out: &mut [F]) {
+    out[0] = f0;
+    for k in 1..out.len() {
+        let s = (1..=k).fold(F::zero(), |acc, j| acc + index::<F>(j) * a[j] * h[k - j]);
+        out[k] = s / index::<F>(k);
+    }
+}
+
+// out = exp(a)
+fn exp_series<F: Scalar>(a: &[F], out: &mut [F]) {
+    out[0] = a[0].exp();
+    for k in 1..out.len() {
+        let s = (1..=k).fold(F::zero(), |acc, j| acc + index::<F>(j) * a[j] * out[k - j]);
+        out[k] = s / index::<F>(k);
+    }
+}
+
+// out = ln(a)
+fn ln_series<F: Scalar>(a: &[F], out: &mut [F]) {
+    out[0] = a[0].ln();
+    for k in 1..out.len() {
+        let s = (1..k).fold(F::zero(), |acc, j| acc + index::<F>(j) * out[j] * a[k - j]);
+        out[k] = (a[k] - s / index::<F>(k)) / a[0];
+    }
+}
+
+// out = a^r
+fn pow_series<F: Scalar>(a: &[F], r: F, out: &mut [F]) {
+    out[0] = a[0].powf(r);
+    for k in 1..out.len() {
+        let kf: F = index(k);
+        let s = (1..=k).fold(F::zero(), |acc, j| {
+            let jf: F = index(j);
+            acc + ((r + F::one()) * jf - kf) * a[j] * out[k - j]
+        });
+        out[k] = s / (kf * a[0]);
+    }
+}
+
+// (s, c) = (sin(a), cos(a)) if hyperbolic is false, (sinh(a), cosh(a)) otherwise
+fn sin_cos_series<F: Scalar>(a: &[F], hyperbolic: bool, s: &mut [F], c: &mut [F]) {
+    if hyperbolic {
+        s[0] = a[0].sinh();
+        c[0] = a[0].cosh();
+    } else {
+        let (s0, c0) = a[0].sin_cos();
+        s[0] = s0;
+        c[0] = c0;
+    }
+    for k in 1..s.len() {
+        let (ss, sc) = (1..=k).fold((F::zero(), F::zero()), |(ss, sc), j| {
+            let ja = index::<F>(j) * a[j];
+            (ss + ja * c[k - j], sc + ja * s[k - j])
+        });
+        s[k] = ss / index::<F>(k);
+        c[k] = if hyperbolic { sc } else { -sc } / index::<F>(k);
+    }
+}
+
+/// Methods for Taylor series that own their content
+impl<T, F> Taylor<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    /// Returns the coefficients as a mutable slice.
+    pub fn as_slice_mut(&mut self) -> &mut [F] {
+        self.content.rw()
+    }
+
+    /// Return a mutable reference to the value.
+    pub fn val_mut(&mut self) -> &mut F {
+        &mut self.as_slice_mut()[0]
+    }
+
+    // Replaces the coefficients by f(self), `f` writing in its second argument.
+    fn apply<G: FnOnce(&[F], &mut [F])>(mut self, f: G) -> Self {
+        let a = self.as_slice().to_vec();
+        f(&a, self.as_slice_mut());
+        self
+    }
+
+    // Replaces the coefficients by g(self) where g' = h(self), `h` writing in its second argument.
+    fn integrate<H: FnOnce(&[F], &mut [F])>(self, g0: F, h: H) -> Self {
+        self.apply(|a, out| {
+            let mut hs = vec![F::zero(); a.len()];
+            h(a, &mut hs);
+            integrate_series(a, &hs, g0, out);
+        })
+    }
+
+    /// Returns e^self.
+    pub fn exp(self) -> Self {
+        self.apply(exp_series)
+    }
+
+    /// Returns 2^self.
+    pub fn exp2(self) -> Self {
+        (self * F::LN_2()).exp()
+    }
+
+    /// Returns ln(self).
+    pub fn ln(self) -> Self {
+        self.apply(ln_series)
+    }
+
+    /// Returns log2(self).
+    pub fn log2(self) -> Self {
+        self.ln() / F::LN_2()
+    }
+
+    /// Returns log10(self).
+    pub fn log10(self) -> Self {
+        self.ln() / F::LN_10()
+    }
+
+    /// Returns 1/self.
+    pub fn inv(self) -> Self {
+        self.apply(|a, out| {
+            let mut one = vec![F::zero(); a.len()];
+            one[0] = F::one();
+            div_series(&one, a, out)
+        })
+    }
+
+    /// Returns self^exp.
+    ///
+    /// The value of self must not be 0.
+    pub fn powf(self, exp: F) -> Self {
+        self.apply(|a, out| pow_series(a, exp, out))
+    }
+
+    /// Returns self^n, by repeated multiplications, so that the value of self can be 0.
+    pub fn powi(self, n: i32) -> Self {
+        let base = if n < 0 { self.inv() } else { self };
+        base.apply(|a, out| {
+            let mut tmp = vec![F::zero(); a.len()];
+            let mut pow = a.to_vec();
+            out.iter_mut().for_each(|x| *x = F::zero());
+            out[0] = F::one();
+            let mut m = n.unsigned_abs();
+            while m > 0 {
+                if m & 1 == 1 {
+                    mul_series(out, &pow, &mut tmp);
+                    out.copy_from_slice(&tmp);
+                }
+                m >>= 1;
+                if m > 0 {
+                    mul_series(&pow, &pow, &mut tmp);
+                    pow.copy_from_slice(&tmp);
+                }
+            }
+        })
+    }
+
+    /// Returns sqrt(self).
+    ///
+    /// The value of self must not be 0. This does not use a temporary buffer.
+    pub fn sqrt(mut self) -> Self {
+        let s = self.as_slice_mut();
+        s[0] = s[0].sqrt();
+        let d = (s[0] + s[0]).recip();
+        for k in 1..s.len() {
+            let sum = (1..k).fold(F::zero(), |acc, j| acc + s[j] * s[k - j]);
+            s[k] = (s[k] - sum) * d;
+        }
+        self
+    }
+
+    /// Returns cbrt(self).
+    ///
+    /// The value of self must not be 0.
+    pub fn cbrt(self) -> Self {
+        let v = self.val();
+        let c = v.cbrt();
+        let mut res = (self / v).powf((F::one() + F::one() + F::one()).recip());
+        res *= c;
+        res
+    }
+
+    /// Returns |self|.
+    ///
+    /// At 0, the coefficients of the positive branch are used, as for `Dual::abs`.
+    pub fn abs(self) -> Self {
+        if self.val() < F::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Returns sin(self).
+    pub fn sin(self) -> Self {
+        self.apply(|a, out| {
+            let mut c = vec![F::zero(); a.len()];
+            sin_cos_series(a, false, out, &mut c)
+        })
+    }
+
+    /// Returns cos(self).
+    pub fn cos(self) -> Self {
+        self.apply(|a, out| {
+            let mut s = vec![F::zero(); a.len()];
+            sin_cos_series(a, false, &mut s, out)
+        })
+    }
+
+    /// Returns tan(self).
+    pub fn tan(self) -> Self {
+        self.apply(|a, out| {
+            let mut s = vec![F::zero(); a.len()];
+            let mut c = vec![F::zero(); a.len()];
+            sin_cos_series(a, false, &mut s, &mut c);
+            div_series(&s, &c, out)
+        })
+    }
+
+    /// Returns asin(self).
+    pub fn asin(self) -> Self {
+        let v = self.val();
+        self.integrate(v.asin(), |a, h| {
+            let mut sq = vec![F::zero(); a.len()];
+            mul_series(a, a, &mut sq);
+            sq.iter_mut().for_each(|x| *x = -*x);
+            sq[0] += F::one();
+            pow_series(&sq, -(F::one() + F::one()).recip(), h)
+        })
+    }
+
+    /// Returns acos(self).
+    pub fn acos(self) -> Self {
+        let v = self.val();
+        let mut res = -self.asin();
+        *res.val_mut() = v.acos();
+        res
+    }
+
+    /// Returns atan(self).
+    pub fn atan(self) -> Self {
+        let v = self.val();
+        self.integrate(v.atan(), |a, h| {
+            let mut sq = vec![F::zero(); a.len()];
+            mul_series(a, a, &mut sq);
+            sq[0] += F::one();
+            let mut one = vec![F::zero(); a.len()];
+            one[0] = F::one();
+            div_series(&one, &sq, h)
+        })
+    }
+
+    /// Returns sinh(self).
+    pub fn sinh(self) -> Self {
+        self.apply(|a, out| {
+            let mut c = vec![F::zero(); a.len()];
+            sin_cos_series(a, true, out, &mut c)
+        })
+    }
+
+    /// Returns cosh(self).
+    pub fn cosh(self) -> Self {
+        self.apply(|a, out| {
+            let mut s = vec![F::zero(); a.len()];
+            sin_cos_series(a, true, &mut s, out)
+        })
+    }
+
+    /// Returns tanh(self).
+    pub fn tanh(self) -> Self {
+        self.apply(|a, out| {
+            let mut s = vec![F::zero(); a.len()];
+            let mut c = vec![F::zero(); a.len()];
+            sin_cos_series(a, true, &mut s, &mut c);
+            div_series(&s, &c, out)
+        })
+    }
+
+    /// Returns asinh(self).
+    pub fn asinh(self) -> Self {
+        let v = self.val();
+        self.integrate(v.asinh(), |a, h| {
+            let mut sq = vec![F::zero(); a.len()];
+            mul_series(a, a, &mut sq);
+            sq[0] += F::one();
+            pow_series(&sq, -(F::one() + F::one()).recip(), h)
+        })
+    }
+
+    /// Returns acosh(self).
+    pub fn acosh(self) -> Self {
+        let v = self.val();
+        self.integrate(v.acosh(), |a, h| {
+            let mut sq = vec![F::zero(); a.len()];
+            mul_series(a, a, &mut sq);
+            sq[0] -= F::one();
+            pow_series(&sq, -(F::one() + F::one()).recip(), h)
+        })
+    }
+
+    /// Returns atanh(self).
+    pub fn atanh(self) -> Self {
+        let v = self.val();
+        self.integrate(v.atanh(), |a, h| {
+            let mut sq = vec![F::zero(); a.len()];
+            mul_series(a, a, &mut sq);
+            sq.iter_mut().for_each(|x| *x = -*x);
+            sq[0] += F::one();
+            let mut one = vec![F::zero(); a.len()];
+            one[0] = F::one();
+            div_series(&one, &sq, h)
+        })
+    }
+}
+
+#[cfg(feature = "implicit-clone")]
+mod implicit_clone {
+    use super::*;
+
+    macro_rules! clone_impl {
+        {$fname: ident($($param : ident : $ptype : ty),*)} => {
+            pub fn $fname(&self,$($param : $ptype),*) -> Taylor<T::Owning, RW, F> {
+                    let res = self.to_owning();
+                    res.$fname($($param),*)
+            }
+        }
+    }
+
+    impl<T, F> Taylor<T, RO, F>
+    where
+        T: ToOwning<F>,
+        F: Scalar,
+    {
+        clone_impl!(exp());
+        clone_impl!(exp2());
+        clone_impl!(ln());
+        clone_impl!(log2());
+        clone_impl!(log10());
+        clone_impl!(inv());
+        clone_impl!(powf(exp: F));
+        clone_impl!(powi(n: i32));
+        clone_impl!(sqrt());
+        clone_impl!(cbrt());
+        clone_impl!(abs());
+        clone_impl!(sin());
+        clone_impl!(cos());
+        clone_impl!(tan());
+        clone_impl!(asin());
+        clone_impl!(acos());
+        clone_impl!(atan());
+        clone_impl!(sinh());
+        clone_impl!(cosh());
+        clone_impl!(tanh());
+        clone_impl!(asinh());
+        clone_impl!(acosh());
+        clone_impl!(atanh());
+    }
+}
+
+//
+//
+// Ops Implementations
+// ===================
+//
+//
+
+macro_rules! check_same_order {
+    ($x : ident , $y : ident) => {
+        assert_eq!(
+            $x.order(),
+            $y.order(),
+            "Taylor series have different orders: {} =/= {}.",
+            $x.order(),
+            $y.order()
+        );
+    };
+}
+
+impl<L, R, M, F> ops::AddAssign<&Taylor<R, M, F>> for Taylor<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn add_assign(&mut self, rhs: &Taylor<R, M, F>) {
+        check_same_order!(self, rhs);
+        self.as_slice_mut()
+            .iter_mut()
+            .zip(rhs.as_slice())
+            .for_each(|(xs, xr)| *xs += *xr);
+    }
+}
+
+impl<L, F> ops::AddAssign<F> for Taylor<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn add_assign(&mut self, rhs: F) {
+        *self.val_mut() += rhs;
+    }
+}
+
+derive_ops_for!(Taylor<T, M, F>: Add, AddAssign, add, add_assign);
+
+impl<L, R, M, F> ops::SubAssign<&Taylor<R, M, F>> for Taylor<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn sub_assign(&mut self, rhs: &Taylor<R, M, F>) {
+        check_same_order!(self, rhs);
+        self.as_slice_mut()
+            .iter_mut()
+            .zip(rhs.as_slice())
+            .for_each(|(xs, xr)| *xs -= *xr);
+    }
+}
+
+impl<L, F> ops::SubAssign<F> for Taylor<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn sub_assign(&mut self, rhs: F) {
+        *self.val_mut() -= rhs;
+    }
+}
+
+derive_ops_for!(Taylor<T, M, F>: Sub, SubAssign, sub, sub_assign);
+
+impl<L, R, M, F> ops::MulAssign<&Taylor<R, M, F>> for Taylor<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn mul_assign(&mut self, rhs: &Taylor<R, M, F>) {
+        check_same_order!(self, rhs);
+        // The coefficient of order k only depends on those of order <= k, so go downwards.
+        let r = rhs.as_slice();
+        let s = self.as_slice_mut();
+        for k in (0..s.len()).rev() {
+            s[k] = (0..=k).fold(F::zero(), |acc, j| acc + s[j] * r[k - j]);
+        }
+    }
+}
+
+impl<L, F> ops::MulAssign<F> for Taylor<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn mul_assign(&mut self, rhs: F) {
+        self.as_slice_mut().iter_mut().for_each(|xs| *xs *= rhs);
+    }
+}
+
+derive_ops_for!(Taylor<T, M, F>: Mul, MulAssign, mul, mul_assign);
+
+impl<L, R, M, F> ops::DivAssign<&Taylor<R, M, F>> for Taylor<L, RW, F>
+where
+    M: OwningMode,
+    L: RWAble<F>,
+    R: ROAble<F>,
+    R: CompatibleWith<M, F>,
+    F: Scalar,
+{
+    fn div_assign(&mut self, rhs: &Taylor<R, M, F>) {
+        check_same_order!(self, rhs);
+        // The coefficient of order k only depends on the quotient's ones of order < k, so go upwards.
+        let r = rhs.as_slice();
+        let inv = r[0].recip();
+        let s = self.as_slice_mut();
+        for k in 0..s.len() {
+            let sum = (1..=k).fold(F::zero(), |acc, j| acc + r[j] * s[k - j]);
+            s[k] = (s[k] - sum) * inv;
+        }
+    }
+}
+
+impl<L, F> ops::DivAssign<F> for Taylor<L, RW, F>
+where
+    L: RWAble<F>,
+    F: Scalar,
+{
+    fn div_assign(&mut self, rhs: F) {
+        self.as_slice_mut().iter_mut().for_each(|xs| *xs /= rhs);
+    }
+}
+
+derive_ops_for!(Taylor<T, M, F>: Div, DivAssign, div, div_assign);
+
+impl<T, F> ops::Neg for Taylor<T, RW, F>
+where
+    T: RWAble<F>,
+    F: Scalar,
+{
+    type Output = Self;
+    fn neg(mut self) -> Self {
+        self.as_slice_mut().iter_mut().for_each(|xs| *xs = -*xs);
+        self
+    }
+}
+
+#[cfg(feature = "implicit-clone")]
+impl<T, F> ops::Neg for Taylor<T, RO, F>
+where
+    T: ToOwning<F>,
+    F: Scalar,
+{
+    type Output = Taylor<T::Owning, RW, F>;
+    fn neg(self) -> Self::Output {
+        -self.to_owning()
+    }
+}
+
+scalar_lhs_ops_for!(Taylor<T, M, F>: f32);
+scalar_lhs_ops_for!(Taylor<T, M, F>: f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type T10 = Taylor<Vec<f64>, RW, f64>;
+
+    const ORDER: usize = 10;
+
+    fn var(x: f64) -> T10 {
+        T10::variable(x, ORDER)
+    }
+
+    // Checks that the derivatives of t are those given by d, relatively to k! for small ones
+    fn check<D: Fn(usize) -> f64>(t: T10, d: D, tol: f64) {
+        let mut fact = 1.;
+        for k in 0..=t.order() {
+            fact *= (k as f64).max(1.);
+            let (got, expected) = (t.derivative(k), d(k));
+            assert!(
+                (got - expected).abs() <= tol * expected.abs().max(fact),
+                "order {}: {} =/= {}",
+                k,
+                got,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_elementary() {
+        let x = 0.4_f64;
+        check(var(x).exp(), |_| x.exp(), 1e-14);
+        check(
+            (var(x) * 3.).exp(),
+            |k| 3_f64.powi(k as i32) * (3. * x).exp(),
+            1e-13,
+        );
+        check(
+            var(x).exp2(),
+            |k| 2_f64.ln().powi(k as i32) * x.exp2(),
+            1e-14,
+        );
+        // d^k/dx^k x^r = r (r - 1) ... (r - k + 1) x^(r - k)
+        let falling = |r: f64, k: usize| (0..k).fold(1., |acc, j| acc * (r - j as f64));
+        check(
+            var(x).powf(2.5),
+            |k| falling(2.5, k) * x.powf(2.5 - k as f64),
+            1e-13,
+        );
+        check(
+            var(x).sqrt(),
+            |k| falling(0.5, k) * x.powf(0.5 - k as f64),
+            1e-13,
+        );
+        check(
+            var(x).cbrt(),
+            |k| falling(1. / 3., k) * x.powf(1. / 3. - k as f64),
+            1e-13,
+        );
+        check(
+            var(x).inv(),
+            |k| falling(-1., k) * x.powi(-1 - k as i32),
+            1e-13,
+        );
+        check(
+            var(x).powi(-3),
+            |k| falling(-3., k) * x.powi(-3 - k as i32),
+            1e-13,
+        );
+        check(var(0.).powi(4), |k| if k == 4 { 24. } else { 0. }, 1e-14);
+        // d^k/dx^k ln(x) = (-1)^(k - 1) (k - 1)! / x^k
+        check(
+            var(x).ln(),
+            |k| {
+                if k == 0 {
+                    x.ln()
+                } else {
+                    falling(-1., k - 1) * x.powi(-(k as i32))
+                }
+            },
+            1e-13,
+        );
+        check(
+            var(x).sin(),
+            |k| (x + k as f64 * std::f64::consts::FRAC_PI_2).sin(),
+            1e-14,
+        );
+        check(
+            var(x).cos(),
+            |k| (x + k as f64 * std::f64::consts::FRAC_PI_2).cos(),
+            1e-14,
+        );
+        check(
+            var(x).sinh(),
+            |k| if k % 2 == 0 { x.sinh() } else { x.cosh() },
+            1e-14,
+        );
+        check(
+            var(x).cosh(),
+            |k| if k % 2 == 0 { x.cosh() } else { x.sinh() },
+            1e-14,
+        );
+    }
+
+    #[test]
+    fn test_compositions() {
+        let x = var(0.4);
+        let id = |_: usize| 0.;
+        let check_id = |t: T10| check(t - &var(0.4), id, 1e-12);
+        check_id(x.clone().exp().ln());
+        check_id(x.clone().sin().asin());
+        check_id(x.clone().cos().acos());
+        check_id(x.clone().tan().atan());
+        check_id(x.clone().sinh().asinh());
+        check_id((x.clone() + 1.).cosh().acosh() - 1.);
+        check_id(x.clone().tanh().atanh());
+        check_id(x.clone().log2().exp2());
+        check_id(x.clone() * &x / x.clone() * x.view() / &x);
+        check_id(-(1. - x.clone()) + 1.);
+        check_id(2. / (2. / x.clone()));
+        let s = x.clone().sin();
+        let c = x.clone().cos();
+        check(
+            s.clone() * &s + c.clone() * c,
+            |k| if k == 0 { 1. } else { 0. },
+            1e-13,
+        );
+        check(x.clone().tan() - s / x.clone().cos(), id, 1e-12);
+        check(
+            x.clone().tanh() - x.clone().sinh() / x.clone().cosh(),
+            id,
+            1e-12,
+        );
+        check(x.clone().log10() - x.clone().ln() / 10_f64.ln(), id, 1e-12);
+    }
+
+    #[test]
+    fn test_array_backed() {
+        let x = Taylor::<[f64; 4], RW, f64>::variable(1., 3);
+        let y = (x * x).exp();
+        // exp(x²)''' at 1 = (12x + 8x³) exp(x²)
+        assert!((y.derivative(3) - 20. * 1_f64.exp()).abs() < 1e-12);
+        assert_eq!(x.view().order(), 3);
+        assert!(x < Taylor::<[f64; 4], RW, f64>::constant(1.5, 3));
+        assert_eq!(
+            x.partial_cmp(&Taylor::<[f64; 4], RW, f64>::constant(1., 3)),
+            Some(Ordering::Equal)
+        );
+    }
+}
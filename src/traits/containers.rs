@@ -44,7 +44,7 @@ pub trait ToView<F>: ROAble<F> {
 impl<F> ToView<F> for Vec<F> {
     type ViewType = [F];
     fn view(&self) -> &[F] {
-        &self
+        self
     }
 }
 
@@ -67,6 +67,31 @@ pub trait ToOwning<F>: ROAble<F> {
     fn to_owning(&self) -> Self::Owning;
 }
 
+/// A trait implemented by owning containers which can back a constant `Dual` built from a single scalar.
+///
+/// This is used when no number of differentials is available, for example for `num_traits::Zero::zero`:
+/// the container then holds as many differentials as its size allows, set to 0.
+/// It is thus only implemented for arrays, whose size is known from their type.
+pub trait FromScalar<F>: RWAble<F> {
+    fn from_scalar(value: F) -> Self;
+}
+
+/// A trait implemented by owning containers which can be created from their length, filled with zeros.
+///
+/// This is used by the `drivers` to build their outputs: arrays panic if the length does not match their size.
+pub trait Zeros<F>: RWAble<F> {
+    fn zeros(len: usize) -> Self;
+}
+
+impl<F> Zeros<F> for Vec<F>
+where
+    F: num_traits::Zero + Clone,
+{
+    fn zeros(len: usize) -> Self {
+        vec![F::zero(); len]
+    }
+}
+
 macro_rules! reimpl_To_Owned {
     (<$($gen:tt),*>, $t:ty) => {
 
@@ -100,3 +125,5 @@ reimpl_To_Owned!(<'a, T>, &'a [T]);
 // }
 
 mod impl_arrays;
+#[cfg(feature = "smallvec")]
+mod impl_smallvec;
@@ -1,57 +1,81 @@
 use super::*;
 
-macro_rules! impl_viewable_array {
-    ($n:literal) => {
-        reimpl_To_Owned!(<T>, [T;$n]);
+impl<T, F, const N: usize> ToOwning<F> for [T; N]
+where
+    [T; N]: ToOwned,
+    <[T; N] as ToOwned>::Owned: RWAble<F>,
+    [T; N]: ROAble<F>,
+{
+    type Owning = <[T; N] as ToOwned>::Owned;
+    fn to_owning(&self) -> Self::Owning {
+        self.to_owned()
+    }
+}
+
+impl<F, const N: usize> ToView<F> for [F; N] {
+    type ViewType = [F; N];
+    fn view(&self) -> &[F; N] {
+        self
+    }
+}
+
+/// # Panics
+///
+/// Panics if the array has size 0, as it cannot even hold the value.
+///
+/// ```should_panic
+/// # use fwd_ad::*;
+/// use num_traits::Zero;
+/// let _ = Dual::<[f64; 0], RW, f64>::zero();
+/// ```
+impl<F, const N: usize> FromScalar<F> for [F; N]
+where
+    F: num_traits::Zero + Copy,
+{
+    fn from_scalar(value: F) -> Self {
+        assert!(N >= 1, "FromScalar: an array must hold at least the value");
+        let mut res = [F::zero(); N];
+        res[0] = value;
+        res
+    }
+}
 
-        impl<F> ToView<F> for [F; $n] {
-            type ViewType = [F; $n];
-            fn view(&self) -> &[F; $n] {
-                &self
-            }
-        }
+impl<F, const N: usize> Zeros<F> for [F; N]
+where
+    F: num_traits::Zero + Copy,
+{
+    fn zeros(len: usize) -> Self {
+        assert_eq!(
+            len, N,
+            "Cannot create an array of size {} with length {}.",
+            N, len
+        );
+        [F::zero(); N]
+    }
+}
 
-        impl<F> ToOwning<F> for &[F;$n]
-        where
-            F: Clone
-        {
-            type Owning = [F;$n];
-            fn to_owning(&self) -> [F;$n] {
-                (*self).clone()
-            }
-        }
-    };
+impl<F, const N: usize> ToOwning<F> for &[F; N]
+where
+    F: Clone,
+{
+    type Owning = [F; N];
+    fn to_owning(&self) -> [F; N] {
+        (*self).clone()
+    }
 }
 
-impl_viewable_array!(1);
-impl_viewable_array!(2);
-impl_viewable_array!(3);
-impl_viewable_array!(4);
-impl_viewable_array!(5);
-impl_viewable_array!(6);
-impl_viewable_array!(7);
-impl_viewable_array!(8);
-impl_viewable_array!(9);
-impl_viewable_array!(10);
-impl_viewable_array!(11);
-impl_viewable_array!(12);
-impl_viewable_array!(13);
-impl_viewable_array!(14);
-impl_viewable_array!(15);
-impl_viewable_array!(16);
-impl_viewable_array!(17);
-impl_viewable_array!(18);
-impl_viewable_array!(19);
-impl_viewable_array!(20);
-impl_viewable_array!(21);
-impl_viewable_array!(22);
-impl_viewable_array!(23);
-impl_viewable_array!(24);
-impl_viewable_array!(25);
-impl_viewable_array!(26);
-impl_viewable_array!(27);
-impl_viewable_array!(28);
-impl_viewable_array!(29);
-impl_viewable_array!(30);
-impl_viewable_array!(31);
-impl_viewable_array!(32);
+#[cfg(test)]
+mod tests {
+    use crate::{Dual, RW};
+
+    #[test]
+    fn test_large_arrays() {
+        // Arrays of any size can back duals.
+        let mut x = Dual::<[f64; 41], RW, f64>::constant(2., 40);
+        x.diffs_mut()[39] = 1.;
+        let y = x * x.view();
+        assert_eq!(y.val(), 4.);
+        assert_eq!(y.diffs()[39], 4.);
+        assert_eq!(y.to_owning(), y);
+    }
+}
@@ -0,0 +1,74 @@
+use super::*;
+use smallvec::{Array, SmallVec};
+
+impl<A: Array> ToView<A::Item> for SmallVec<A> {
+    type ViewType = SmallVec<A>;
+    fn view(&self) -> &SmallVec<A> {
+        self
+    }
+}
+
+impl<A> ToOwning<A::Item> for SmallVec<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    type Owning = SmallVec<A>;
+    fn to_owning(&self) -> SmallVec<A> {
+        self.clone()
+    }
+}
+
+impl<A> ToOwning<A::Item> for &SmallVec<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    type Owning = SmallVec<A>;
+    fn to_owning(&self) -> SmallVec<A> {
+        (*self).clone()
+    }
+}
+
+impl<A> Zeros<A::Item> for SmallVec<A>
+where
+    A: Array,
+    A::Item: num_traits::Zero + Clone,
+{
+    fn zeros(len: usize) -> Self {
+        SmallVec::from_elem(num_traits::Zero::zero(), len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drivers::jacobian;
+    use crate::instanciations::smallvec::{Owning, View};
+    use smallvec::{smallvec, SmallVec};
+
+    #[test]
+    fn test_smallvec() {
+        let x = Owning::<f64, 4>::from(smallvec![2., 1., 0.]);
+        let y = Owning::<f64, 4>::from(smallvec![3., 0., 1.]);
+        let v: View<f64, 4> = y.view();
+        let z = x.clone() * v;
+        assert_eq!(z.diffs(), &[3., 2.]);
+        // Views are turned back into SmallVecs.
+        let owned: Owning<f64, 4> = y.view().to_owning();
+        assert_eq!(owned, y);
+        // More derivatives than the inline capacity spill on the heap.
+        let mut big = Owning::<f64, 4>::constant(1., 10);
+        assert!(big.clone().into_container().spilled());
+        big.diffs_mut()[9] = 1.;
+        assert_eq!((big.clone() * big.view()).diffs()[9], 2.);
+        assert!(!x.into_container().spilled());
+    }
+
+    #[test]
+    fn test_drivers() {
+        let f = |x: &[Owning<f64, 3>]| vec![x[0].clone() * x[1].view(), x[1].clone().exp()];
+        let (val, jac): (SmallVec<[f64; 2]>, Vec<f64>) = jacobian(f, &[2., 0.]);
+        assert_eq!(val.as_slice(), &[0., 1.]);
+        assert_eq!(jac, vec![0., 2., 0., 1.]);
+    }
+}
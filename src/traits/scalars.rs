@@ -2,7 +2,9 @@ use num_traits::*;
 
 /// Indicates that a type can be used as a scalar.
 ///
-/// Implemented for `f32` and `f64`.
+/// Implemented for `f32` and `f64`, as well as RW `Dual`s backed by arrays of those, which can hence be nested.
+/// `Dual`s backed by a `Vec` are not `Scalar`s, as `Float` requires `Copy`, so that they can only be used
+/// as the outermost level of nested duals.
 pub trait Scalar: NumAssignOps + NumRef + NumAssignRef + real::Real + float::FloatConst {}
 
 impl<F> Scalar for F where F: NumAssignOps + NumRef + NumAssignRef + real::Real + float::FloatConst {}
@@ -1,3 +1,4 @@
 #!/bin/sh
 cargo test --verbose
 cargo test --verbose --features implicit-clone
+cargo test --verbose --features smallvec